@@ -1,25 +1,44 @@
-use super::{read_file, Part, Result};
-use anyhow::Error;
+use super::{read_input, Opt, Part, Result};
+
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let input = read_input(opt, "input/day_1")?;
 
-pub fn main(part: Part) -> Result<()> {
     match part {
-        Part::One => {}
-        Part::Two => return Err(Error::msg("Day 1, part 2 is lost")),
+        Part::One => println!("{}", part_1(&input)?),
+        Part::Two => println!("{}", part_2(&input)?),
     }
 
+    Ok(())
+}
+
+/// The fuel needed for every module, one mass per line.
+pub fn part_1(input: &str) -> Result<i64> {
+    total(input, module_fuel)
+}
+
+/// Like `part_1`, but including the fuel needed for the fuel.
+pub fn part_2(input: &str) -> Result<i64> {
+    total(input, fuel)
+}
+
+fn total(input: &str, fuel: impl Fn(i64) -> i64) -> Result<i64> {
     let mut total = 0;
 
-    for line in read_file("input/day_1")?.lines() {
-        let mass = line.parse::<i64>()?;
+    for line in input.lines() {
+        let mass = line.trim().parse::<i64>()?;
         total += fuel(mass);
     }
 
-    dbg!(total);
+    Ok(total)
+}
 
-    Ok(())
+/// The fuel needed to launch `mass`, not counting the mass of the fuel itself.
+pub fn module_fuel(mass: i64) -> i64 {
+    (mass / 3 - 2).max(0)
 }
 
-fn fuel(mass: i64) -> i64 {
+/// The fuel needed to launch `mass`, plus the fuel needed to launch that fuel, and so on.
+pub fn fuel(mass: i64) -> i64 {
     let n = (mass / 3) - 2;
     if n < 0 {
         0
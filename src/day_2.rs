@@ -1,132 +1,63 @@
-use super::{read_file, Part, Error, Result};
+use super::{read_input, Error, Opt, Part, Result};
+use crate::input::parse_ints;
+use crate::intcode::IntMachine;
 use rayon::prelude::*;
-use std::fmt;
 
-pub fn main(part: Part) -> Result<()> {
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let input = read_input(opt, "input/day_2")?;
+
     match part {
-        Part::One => {}
-        Part::Two => return Err(Error::msg("Day 1, part 2 is lost")),
+        Part::One => {
+            let (noun, verb) = (opt.noun.unwrap_or(12), opt.verb.unwrap_or(2));
+            println!("{}", run_with(&parse_ints(&input)?, noun, verb)?);
+        }
+        Part::Two => println!("{}", part_2(&input)?),
     }
 
-    let input = read_file("input/day_2")?;
-    let mem = parse_input_to_mem(&input)?;
-
-    let result = (0..=99)
-        .into_par_iter()
-        .flat_map(|noun| (0..=99).into_par_iter().map(move |verb| (noun, verb)))
-        .find_first(|(noun, verb)| {
-            let mut machine = IntMachine::new(&mem);
-
-            machine.mem[1] = *noun;
-            machine.mem[2] = *verb;
-
-            machine.run_to_completion().ok().unwrap();
-
-            let output = machine.mem[0];
-
-            19_690_720 == output
-        });
-
-    let (noun, verb) = result.ok_or_else(|| Error::msg("no match"))?;
-    println!("{}", 100 * noun + verb);
-
     Ok(())
 }
 
-fn parse_input_to_mem(input: &str) -> Result<Vec<i32>> {
-    input
-        .split(',')
-        .map(|i| i.replace('\n', "").parse::<i32>())
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(From::from)
+/// The value left at address 0 after running the program with noun 12 and verb 2.
+pub fn part_1(input: &str) -> Result<i32> {
+    run_with(&parse_ints(input)?, 12, 2)
 }
 
-#[derive(Debug)]
-struct IntMachine {
-    mem: Vec<i32>,
-    ip: usize,
-}
-
-impl IntMachine {
-    fn new(mem: &[i32]) -> Self {
-        Self {
-            mem: mem.to_vec(),
-            ip: 0,
-        }
-    }
-
-    fn run_to_completion(&mut self) -> Result<()> {
-        loop {
-            match self.tick()? {
-                TickOutput::Done => break,
-                TickOutput::NotDone => {}
-            }
-        }
-
-        Ok(())
-    }
+/// Find the noun and verb that make the program output 19690720, as `100 * noun + verb`.
+pub fn part_2(input: &str) -> Result<i32> {
+    let mem = parse_ints(input)?;
+    let (noun, verb) = noun_and_verb(&mem, 19_690_720).ok_or_else(|| Error::msg("no match"))?;
 
-    fn tick(&mut self) -> Result<TickOutput> {
-        match self.next_ops()? {
-            OpCode::Add => self.run_arithmetic_op(|a, b| a + b),
-            OpCode::Mul => self.run_arithmetic_op(|a, b| a * b),
-            OpCode::Halt => Ok(TickOutput::Done),
-        }
-    }
-
-    fn run_arithmetic_op(&mut self, f: impl Fn(i32, i32) -> i32) -> Result<TickOutput> {
-        let a_pos = self.mem[self.ip + 1];
-        let a = self.mem[a_pos as usize];
-
-        let b_pos = self.mem[self.ip + 2];
-        let b = self.mem[b_pos as usize];
-
-        let destination = self.mem[self.ip + 3] as usize;
-        self.mem[destination] = f(a, b);
-
-        self.ip += 4;
-        Ok(TickOutput::NotDone)
-    }
-
-    fn next_ops(&self) -> Result<OpCode> {
-        match self.mem[self.ip] {
-            99 => Ok(OpCode::Halt),
-            1 => Ok(OpCode::Add),
-            2 => Ok(OpCode::Mul),
-            code => Err(Error::msg(format!("Invalid op code {}", code))),
-        }
-    }
+    Ok(100 * noun + verb)
 }
 
-enum OpCode {
-    Add,
-    Mul,
-    Halt,
-}
-
-enum TickOutput {
-    Done,
-    NotDone,
-}
-
-impl fmt::Display for IntMachine {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mem = self
-            .mem
-            .iter()
-            .map(|n| n.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-        write!(f, "{}", mem)
+/// The noun and verb, both between 0 and 99, that make `program` leave `target` at address 0.
+/// The one with the smallest noun, then verb, if there are several.
+pub fn noun_and_verb(program: &[i32], target: i32) -> Option<(i32, i32)> {
+    (0..=99)
+        .into_par_iter()
+        .flat_map(|noun| (0..=99).into_par_iter().map(move |verb| (noun, verb)))
+        .find_first(|(noun, verb)| {
+            run_with(program, *noun, *verb)
+                .map(|output| output == target)
+                .unwrap_or(false)
+        })
+}
+
+/// Run `program` with `noun` at address 1 and `verb` at address 2, and return the value left at
+/// address 0.
+fn run_with(program: &[i32], noun: i32, verb: i32) -> Result<i32> {
+    if program.len() < 3 {
+        return Err(Error::msg(format!(
+            "a program of {} integers has no room for a noun and a verb",
+            program.len()
+        )));
     }
-}
 
-#[test]
-fn test() {
-    let ops = "1,9,10,3,2,3,11,0,99,30,40,50";
-    let mem = parse_input_to_mem(ops).unwrap();
-    let mut machine = IntMachine::new(&mem);
-    machine.run_to_completion().unwrap();
+    let mut machine = IntMachine::new(program);
+
+    machine.mem_mut()[1] = noun;
+    machine.mem_mut()[2] = verb;
+    machine.run_to_completion()?;
 
-    assert_eq!(machine.to_string(), "3500,9,10,70,2,3,11,0,99,30,40,50");
+    Ok(machine.mem()[0])
 }
@@ -1,38 +1,57 @@
-use super::{read_file, Error, Part, Result};
-use std::collections::HashSet;
+#[cfg(test)]
+use super::error_message;
+use super::{read_input, Error, Opt, Part, Result};
+use crate::geom::{self, manhattan_distance, Direction, Point};
+#[cfg(test)]
+use std::collections::{HashMap, HashSet};
 
 mod part_1;
 mod part_2;
+mod render;
+mod segment;
+mod wire_set;
+
+pub use render::svg;
+pub use wire_set::{Crossing, Metric, WireSet};
+
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let input = read_input(opt, "input/day_3")?;
+
+    if let Some(path) = &opt.render {
+        return std::fs::write(path, render::svg(&input)?).map_err(From::from);
+    }
 
-pub fn main(part: Part) -> Result<()> {
     match part {
-        Part::One => part_1::main(),
-        Part::Two => part_2::main(),
+        Part::One => println!("{}", part_1(&input)?),
+        Part::Two => println!("{}", part_2(&input)?),
     }
-}
 
-fn parse_moves(line: &str) -> Result<Vec<Move>> {
-    line.split(',')
-        .map(|s| s.replace("\n", ""))
-        .map(|s| parse_move(&s))
-        .collect::<Result<Vec<_>>>()
+    Ok(())
 }
 
-fn parse_move(m: &str) -> Result<Move> {
-    let parts = m.split("").filter(|s| !s.is_empty()).collect::<Vec<_>>();
-
-    let direction = &parts[0];
-    let maginutde = parts[1..].join("").parse::<i32>()?;
+/// The Manhattan distance from the origin to the closest crossing of the wires, one per line.
+pub fn part_1(input: &str) -> Result<i32> {
+    part_1::closest_intersection(input)
+}
 
-    let move_ = match direction {
-        &"U" => Move::Up(maginutde),
-        &"D" => Move::Down(maginutde),
-        &"R" => Move::Right(maginutde),
-        &"L" => Move::Left(maginutde),
-        dir => return Err(Error::msg(format!("Invalid movement direction {}", dir))),
-    };
+/// The fewest combined steps the wires take to reach a crossing.
+pub fn part_2(input: &str) -> Result<i32> {
+    part_2::fewest_combined_steps(input)
+}
 
-    Ok(move_)
+/// Parse one wire per line.
+fn parse_wires(input: &str) -> Result<Vec<Vec<Move>>> {
+    let wires = geom::parse_steps(input)?
+        .into_iter()
+        .map(|steps| {
+            steps
+                .into_iter()
+                .map(|(direction, maginutde)| Move::new(direction, maginutde))
+                .collect()
+        })
+        .collect();
+
+    Ok(wires)
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -44,88 +63,123 @@ enum Move {
 }
 
 impl Move {
-    fn into_single_moves(self) -> Vec<Self> {
-        let range = |n| (1..=n).map(|_| 1);
+    fn new(direction: Direction, maginutde: i32) -> Self {
+        match direction {
+            Direction::Up => Move::Up(maginutde),
+            Direction::Down => Move::Down(maginutde),
+            Direction::Left => Move::Left(maginutde),
+            Direction::Right => Move::Right(maginutde),
+        }
+    }
 
+    fn direction(self) -> Direction {
         match self {
-            Move::Up(maginutde) => range(maginutde).map(Move::Up).collect(),
-            Move::Down(maginutde) => range(maginutde).map(Move::Down).collect(),
-            Move::Left(maginutde) => range(maginutde).map(Move::Left).collect(),
-            Move::Right(maginutde) => range(maginutde).map(Move::Right).collect(),
+            Move::Up(_) => Direction::Up,
+            Move::Down(_) => Direction::Down,
+            Move::Left(_) => Direction::Left,
+            Move::Right(_) => Direction::Right,
         }
     }
+
+    fn magnitude(self) -> i32 {
+        match self {
+            Move::Up(maginutde)
+            | Move::Down(maginutde)
+            | Move::Left(maginutde)
+            | Move::Right(maginutde) => maginutde,
+        }
+    }
+
+    #[cfg(test)]
+    fn into_single_moves(self) -> Vec<Self> {
+        (0..self.magnitude())
+            .map(|_| Move::new(self.direction(), 1))
+            .collect()
+    }
 }
 
+/// A wire walked one cell at a time, for the solutions that check the segment based ones.
+#[cfg(test)]
 #[derive(Debug)]
 struct WirePath {
-    current_position: Pos,
-    previous_positions: Vec<Pos>,
+    current_position: Point,
+    steps: usize,
+    first_visits: HashMap<Point, usize>,
 }
 
+#[cfg(test)]
 impl WirePath {
     fn new() -> Self {
         Self {
-            current_position: Pos { x: 0, y: 0 },
-            previous_positions: Vec::new(),
+            current_position: Point::zero(),
+            steps: 0,
+            first_visits: HashMap::new(),
         }
     }
 
-    fn current_position(&self) -> Pos {
+    fn current_position(&self) -> Point {
         self.current_position
     }
 
-    fn all_positions(&self) -> Vec<Pos> {
-        let mut positions = self.previous_positions.clone();
-        positions.push(self.current_position());
-        positions
+    fn steps(&self) -> usize {
+        self.steps
     }
 
-    fn apply(&mut self, move_: Move) {
-        self.previous_positions.push(self.current_position());
-
-        let mut next_pos = self.current_position;
-
-        match move_ {
-            Move::Up(maginutde) => next_pos.y += maginutde,
-            Move::Down(maginutde) => next_pos.y -= maginutde,
-            Move::Right(maginutde) => next_pos.x += maginutde,
-            Move::Left(maginutde) => next_pos.x -= maginutde,
-        }
-
-        self.current_position = next_pos;
+    /// The number of steps the wire had taken the first time it reached `pos`, counting every
+    /// cell it passes through.
+    fn steps_to(&self, pos: Point) -> Option<usize> {
+        self.first_visits.get(&pos).copied()
     }
-}
-
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
 
-impl Pos {
-    fn zero() -> Self {
-        Self { x: 0, y: 0 }
+    fn apply(&mut self, move_: Move) {
+        for _ in 0..move_.magnitude() {
+            self.current_position = self.current_position.step(move_.direction(), 1);
+            self.steps += 1;
+            self.first_visits
+                .entry(self.current_position)
+                .or_insert(self.steps);
+        }
     }
 }
 
-fn manhattan_distance(p: Pos, q: Pos) -> i32 {
-    (p.x - q.x).abs() + (p.y - q.y).abs()
+#[test]
+fn test_parse_move() {
+    use Move::*;
+    let wires = parse_wires("L1008,D451,L146,D628\nU7,R6").unwrap();
+    assert_eq!(
+        wires,
+        vec![
+            vec![Left(1008), Down(451), Left(146), Down(628)],
+            vec![Up(7), Right(6)]
+        ]
+    );
 }
 
 #[test]
-fn manhattan_distance_test() {
-    let p = Pos { x: 0, y: 0 };
+fn wire_path_test() {
+    let mut wire_path = WirePath::new();
+    wire_path.apply(Move::Right(3));
+    wire_path.apply(Move::Up(2));
+    wire_path.apply(Move::Left(1));
+    wire_path.apply(Move::Down(4));
+
+    assert_eq!(wire_path.steps(), 10);
+    assert_eq!(wire_path.current_position(), Point::new(2, -2));
+    assert_eq!(wire_path.steps_to(Point::new(1, 0)), Some(1));
+    assert_eq!(wire_path.steps_to(Point::new(3, 1)), Some(4));
+    // Crossed again on the way down, but the first visit counts.
+    assert_eq!(wire_path.steps_to(Point::new(2, 0)), Some(2));
+    assert_eq!(wire_path.steps_to(Point::new(0, 1)), None);
+}
 
-    let q = Pos { x: 3, y: 3 };
-    assert_eq!(manhattan_distance(p, q), 6);
+/// Two short random wires, like `R8,U5,L5\nU7,R6`.
+#[cfg(test)]
+fn wires() -> impl proptest::strategy::Strategy<Value = String> {
+    use proptest::prelude::*;
 
-    let q = Pos { x: 6, y: 5 };
-    assert_eq!(manhattan_distance(p, q), 11);
-}
+    let move_ = (prop::sample::select(vec!['U', 'D', 'L', 'R']), 1..20)
+        .prop_map(|(direction, n)| format!("{}{}", direction, n));
+    let wire = prop::collection::vec(move_, 1..8).prop_map(|moves| moves.join(","));
 
-#[test]
-fn test_parse_move() {
-    use Move::*;
-    let moves = parse_moves("L1008,D451,L146,D628").unwrap();
-    assert_eq!(moves, vec![Left(1008), Down(451), Left(146), Down(628)]);
+    (wire.clone(), wire).prop_map(|(a, b)| format!("{}\n{}", a, b))
 }
@@ -1,20 +1,29 @@
 use super::*;
+#[cfg(test)]
 use rayon::prelude::*;
 
-pub fn main() -> Result<()> {
-    let input = read_file("input/day_3")?;
+pub fn closest_intersection(input: &str) -> Result<i32> {
+    WireSet::parse(input)?
+        .closest(Metric::Manhattan)
+        .map(|crossing| crossing.distance(Metric::Manhattan))
+        .ok_or_else(|| Error::msg("no min"))
+}
 
-    let mut current_wire_positions = HashSet::<Pos>::new();
-    let mut previous_wire_positions = HashSet::<Pos>::new();
-    let mut intersections = HashSet::<Pos>::new();
+/// Find the closest intersection by walking every wire one cell at a time.
+///
+/// Needs memory proportional to the total length of the wires, but is kept around to check
+/// `closest_intersection` against.
+#[cfg(test)]
+fn closest_intersection_by_cells(input: &str) -> Result<i32> {
+    let mut current_wire_positions = HashSet::<Point>::new();
+    let mut previous_wire_positions = HashSet::<Point>::new();
+    let mut intersections = HashSet::<Point>::new();
 
-    for line in input.lines() {
+    for moves in parse_wires(input)? {
         for position_from_previous_wire_path in current_wire_positions.drain() {
             previous_wire_positions.insert(position_from_previous_wire_path);
         }
 
-        let moves = parse_moves(line)?;
-
         let mut wire_path = WirePath::new();
 
         for move_ in moves {
@@ -24,7 +33,10 @@ pub fn main() -> Result<()> {
 
                 current_wire_positions.insert(current_position);
 
-                if previous_wire_positions.contains(&current_position) {
+                // Wires that wander back to the origin don't cross there.
+                if current_position != Point::zero()
+                    && previous_wire_positions.contains(&current_position)
+                {
                     intersections.insert(current_position);
                 }
             }
@@ -33,10 +45,44 @@ pub fn main() -> Result<()> {
 
     let min = intersections
         .into_par_iter()
-        .min_by_key(|pos| manhattan_distance(Pos::zero(), *pos))
+        .min_by_key(|pos| manhattan_distance(Point::zero(), *pos))
         .ok_or_else(|| Error::msg("no min"))?;
 
-    println!("{}", manhattan_distance(Pos::zero(), min));
+    Ok(manhattan_distance(Point::zero(), min))
+}
+
+#[test]
+fn closest_intersection_test() {
+    let examples = vec![
+        ("R8,U5,L5,D3\nU7,R6,D4,L4", 6),
+        (
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
+            159,
+        ),
+        (
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            135,
+        ),
+    ];
 
-    Ok(())
+    for (input, expected) in examples {
+        assert_eq!(closest_intersection(input).unwrap(), expected, "{}", input);
+        assert_eq!(
+            closest_intersection_by_cells(input).unwrap(),
+            expected,
+            "{}",
+            input
+        );
+    }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn closest_intersection_matches_cells(input in wires()) {
+        proptest::prop_assert_eq!(
+            closest_intersection(&input).ok(),
+            closest_intersection_by_cells(&input).ok()
+        );
+    }
 }
@@ -1,75 +1,104 @@
 use super::*;
-use rayon::prelude::*;
 
-pub fn main() -> Result<()> {
-    let input = read_file("input/day_3")?;
-
-    let mut current_wire_positions = HashSet::<Pos>::new();
-    let mut previous_wire_positions = HashSet::<Pos>::new();
-    let mut intersections = HashSet::<Pos>::new();
-
-    let wire_paths = input
-        .lines()
-        .map(|line| {
-            for position_from_previous_wire_path in current_wire_positions.drain() {
-                previous_wire_positions.insert(position_from_previous_wire_path);
-            }
+pub fn fewest_combined_steps(input: &str) -> Result<i32> {
+    WireSet::parse(input)?
+        .closest(Metric::Steps)
+        .map(|crossing| crossing.distance(Metric::Steps))
+        .ok_or_else(|| Error::msg("no min"))
+}
 
-            let moves = parse_moves(line)?;
+/// Find the fewest combined steps by walking every wire one cell at a time.
+///
+/// Needs memory proportional to the total length of the wires, but is kept around to check
+/// `fewest_combined_steps` against.
+#[cfg(test)]
+fn fewest_combined_steps_by_cells(input: &str) -> Result<i32> {
+    let mut previous_wire_paths = Vec::<WirePath>::new();
+    let mut min = None::<usize>;
 
-            let mut wire_path = WirePath::new();
+    for moves in parse_wires(input)? {
+        let mut wire_path = WirePath::new();
 
-            for move_ in moves {
-                for single_move in move_.into_single_moves() {
-                    wire_path.apply(single_move);
-                    let current_position = wire_path.current_position();
+        for move_ in moves {
+            for single_move in move_.into_single_moves() {
+                wire_path.apply(single_move);
+                let current_position = wire_path.current_position();
 
-                    current_wire_positions.insert(current_position);
+                // Only the first visit to a position counts, and wires that wander back to the
+                // origin don't cross there.
+                if current_position == Point::zero()
+                    || wire_path.steps_to(current_position) != Some(wire_path.steps())
+                {
+                    continue;
+                }
 
-                    if previous_wire_positions.contains(&current_position) {
-                        intersections.insert(current_position);
+                for previous_wire_path in &previous_wire_paths {
+                    if let Some(steps) = previous_wire_path.steps_to(current_position) {
+                        let combined = steps + wire_path.steps();
+                        min = Some(min.map_or(combined, |min| min.min(combined)));
                     }
                 }
             }
+        }
 
-            Ok(wire_path)
-        })
-        .collect::<Result<Vec<_>>>()?;
-
-    let min = intersections
-        .par_iter()
-        .map(|intersection| {
-            let sum = wire_paths
-                .par_iter()
-                .map(move |wire_path| distance(*intersection, &wire_path.all_positions()))
-                .collect::<Result<Vec<usize>>>()?
-                .into_par_iter()
-                .sum::<usize>();
-
-            Ok(sum)
-        })
-        .collect::<Result<Vec<_>>>()?
-        .into_par_iter()
-        .min()
-        .ok_or_else(|| Error::msg("no min"))?;
-
-    println!("{}", min);
-
-    Ok(())
-}
+        previous_wire_paths.push(wire_path);
+    }
 
-fn distance(intersection: Pos, paths: &[Pos]) -> Result<usize> {
-    paths
-        .par_iter()
-        .enumerate()
-        .filter_map(|(step_count, pos)| {
-            if pos == &intersection {
-                Some(step_count)
-            } else {
-                None
-            }
-        })
-        .min()
+    min.map(|min| min as i32)
         .ok_or_else(|| Error::msg("no min"))
-        .map_err(From::from)
+}
+
+#[test]
+fn fewest_combined_steps_test() {
+    let examples = vec![
+        ("R8,U5,L5,D3\nU7,R6,D4,L4", 30),
+        (
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
+            610,
+        ),
+        (
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            410,
+        ),
+    ];
+
+    for (input, expected) in examples {
+        assert_eq!(fewest_combined_steps(input).unwrap(), expected, "{}", input);
+        assert_eq!(
+            fewest_combined_steps_by_cells(input).unwrap(),
+            expected,
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn fewest_combined_steps_bench() {
+    use std::time::Instant;
+
+    // Run with `cargo test --release fewest_combined_steps_bench -- --ignored --nocapture`
+    let input = crate::read_file("input/day_3").unwrap();
+
+    let start = Instant::now();
+    let by_segments = fewest_combined_steps(&input).unwrap();
+    println!("segments: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let by_cells = fewest_combined_steps_by_cells(&input).unwrap();
+    println!("cells: {:?}", start.elapsed());
+
+    assert_eq!(by_segments, by_cells);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn fewest_combined_steps_matches_cells(input in wires()) {
+        proptest::prop_assert_eq!(
+            fewest_combined_steps(&input).ok(),
+            fewest_combined_steps_by_cells(&input).ok()
+        );
+    }
 }
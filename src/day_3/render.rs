@@ -0,0 +1,116 @@
+use super::segment::{self, Segment};
+use super::*;
+use crate::geom::Bounds;
+use std::fmt::Write;
+use std::iter;
+
+const COLOURS: &[&str] = &[
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/// Draw every wire as a polyline, the origin, and every crossing.
+///
+/// The crossing closest to the origin by Manhattan distance is circled in red, and the one with
+/// the fewest combined steps in a wider blue ring, so both stay visible when they are the same one.
+pub fn svg(input: &str) -> Result<String> {
+    let wires = WireSet::parse(input)?;
+    let wire_corners = parse_wires(input)?
+        .iter()
+        .map(|moves| {
+            Ok(iter::once(Point::zero())
+                .chain(segment::segments(moves)?.iter().map(Segment::end))
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let crossings = wires.crossings();
+    let closest_by_distance = wires.closest(Metric::Manhattan);
+    let closest_by_steps = wires.closest(Metric::Steps);
+
+    let Bounds { min, max } = Bounds::from_points(wire_corners.iter().flatten().copied())
+        .unwrap_or(Bounds {
+            min: Point::zero(),
+            max: Point::zero(),
+        });
+
+    let size = (max.x - min.x).max(max.y - min.y).max(1);
+    let stroke = size as f64 / 1000.0;
+    let marker = stroke * 4.0;
+    let padding = marker * 4.0;
+
+    let mut svg = String::new();
+
+    // Wires go up for positive y, but SVG's y axis points down, so y is negated throughout.
+    writeln!(
+        svg,
+        "<svg xmlns='http://www.w3.org/2000/svg' viewBox='{} {} {} {}'>",
+        min.x as f64 - padding,
+        -max.y as f64 - padding,
+        (max.x - min.x) as f64 + 2.0 * padding,
+        (max.y - min.y) as f64 + 2.0 * padding,
+    )?;
+
+    for (idx, corners) in wire_corners.iter().enumerate() {
+        let points = corners
+            .iter()
+            .map(|pos| format!("{},{}", pos.x, -pos.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(
+            svg,
+            "<polyline points='{}' fill='none' stroke='{}' stroke-width='{}'/>",
+            points,
+            COLOURS[idx % COLOURS.len()],
+            stroke,
+        )?;
+    }
+
+    for crossing in &crossings {
+        writeln!(
+            svg,
+            "<circle cx='{}' cy='{}' r='{}' fill='black'/>",
+            crossing.pos.x, -crossing.pos.y, marker,
+        )?;
+    }
+
+    let highlights = vec![
+        (closest_by_distance, "red", marker * 3.0),
+        (closest_by_steps, "blue", marker * 4.5),
+    ];
+    for (crossing, colour, radius) in highlights {
+        if let Some(crossing) = crossing {
+            writeln!(
+                svg,
+                "<circle cx='{}' cy='{}' r='{}' fill='none' stroke='{}' stroke-width='{}'/>",
+                crossing.pos.x, -crossing.pos.y, radius, colour, stroke,
+            )?;
+        }
+    }
+
+    writeln!(
+        svg,
+        "<rect x='{}' y='{}' width='{}' height='{}' fill='black'/>",
+        -marker,
+        -marker,
+        marker * 2.0,
+        marker * 2.0,
+    )?;
+
+    writeln!(svg, "</svg>")?;
+
+    Ok(svg)
+}
+
+#[test]
+fn svg_test() {
+    let svg = svg("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+
+    assert!(svg.contains("<polyline points='0,0 8,0 8,-5 3,-5 3,-2'"));
+    assert!(svg.contains("<polyline points='0,0 0,-7 6,-7 6,-3 2,-3'"));
+    assert!(svg.contains("<circle cx='3' cy='-3'"));
+    assert!(svg.contains("<circle cx='6' cy='-5'"));
+    assert!(svg.contains("<circle cx='3' cy='-3' r='0.096' fill='none' stroke='red'"));
+    assert!(svg.contains("<circle cx='6' cy='-5' r='0.14"));
+    assert!(svg.contains("stroke='blue'"));
+}
@@ -0,0 +1,393 @@
+use super::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// A horizontal or vertical piece of a wire, along with the number of steps the wire had taken
+/// when it reached `start`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Segment {
+    start: Point,
+    end: Point,
+    steps: i32,
+}
+
+impl Segment {
+    /// Where the wire is once it has run the length of the segment.
+    pub fn end(&self) -> Point {
+        self.end
+    }
+
+    /// The number of steps the wire has taken by the end of the segment.
+    pub fn length(&self) -> i32 {
+        self.steps + manhattan_distance(self.start, self.end)
+    }
+
+    fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    fn x_range(&self) -> (i32, i32) {
+        (self.start.x.min(self.end.x), self.start.x.max(self.end.x))
+    }
+
+    fn y_range(&self) -> (i32, i32) {
+        (self.start.y.min(self.end.y), self.start.y.max(self.end.y))
+    }
+
+    /// The number of steps the wire has taken when it reaches `pos`, which must be on the segment.
+    fn steps_to(&self, pos: Point) -> i32 {
+        self.steps + manhattan_distance(self.start, pos)
+    }
+}
+
+/// Split a wire into its segments.
+///
+/// Fails if the wire runs more steps than fit in an `i32`.
+pub fn segments(moves: &[Move]) -> Result<Vec<Segment>> {
+    let mut start = Point::zero();
+    let mut steps = 0i32;
+
+    moves
+        .iter()
+        .enumerate()
+        .map(|(idx, move_)| {
+            let end = start.checked_step(move_.direction(), move_.magnitude());
+            let next_steps = steps.checked_add(move_.magnitude());
+
+            let (end, next_steps) = end.zip(next_steps).ok_or_else(|| {
+                Error::msg(format!(
+                    "move {} takes the wire more than {} steps",
+                    idx + 1,
+                    i32::MAX
+                ))
+            })?;
+            let segment = Segment { start, end, steps };
+
+            steps = next_steps;
+            start = end;
+
+            Ok(segment)
+        })
+        .collect()
+}
+
+/// A point where segments from two different wires meet, and the number of steps each wire has
+/// taken along its segment to get there.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Intersection {
+    pub pos: Point,
+    pub wires: [(usize, i32); 2],
+}
+
+/// Find every point where segments from two different wires meet, except the origin.
+///
+/// Perpendicular crossings are found with a sweep line moving along the x axis. Horizontal
+/// segments are kept in a map keyed by their y coordinate while the sweep line is within their
+/// x range, and each vertical segment queries the map for the horizontal segments in its y range.
+/// Segments that lie on top of each other are handled separately by `overlaps`, which reports
+/// every shared point.
+pub fn intersections(wires: &[Vec<Segment>]) -> Vec<Intersection> {
+    sweep(wires, Overlap::Every)
+}
+
+/// Like `intersections`, but only report the points of each overlap between collinear segments
+/// that can be closest to the origin, either by Manhattan distance or by steps.
+///
+/// Long overlaps then cost a handful of intersections instead of one per shared point.
+pub fn nearest_intersections(wires: &[Vec<Segment>]) -> Vec<Intersection> {
+    sweep(wires, Overlap::Nearest)
+}
+
+/// Which points `overlaps` reports for a stretch shared by collinear segments.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Overlap {
+    Every,
+    Nearest,
+}
+
+fn sweep(wires: &[Vec<Segment>], overlap: Overlap) -> Vec<Intersection> {
+    let mut events = Vec::new();
+
+    for (wire, segments) in wires.iter().enumerate() {
+        for segment in segments {
+            if segment.is_horizontal() {
+                let (lo, hi) = segment.x_range();
+                events.push(Event::new(lo, EventKind::Insert, wire, *segment));
+                events.push(Event::new(hi, EventKind::Remove, wire, *segment));
+            } else {
+                events.push(Event::new(
+                    segment.start.x,
+                    EventKind::Query,
+                    wire,
+                    *segment,
+                ));
+            }
+        }
+    }
+
+    events.sort_by_key(|event| (event.x, event.kind));
+
+    let mut active = BTreeMap::<i32, Vec<(usize, Segment)>>::new();
+    let mut intersections = overlaps(wires, overlap);
+
+    for Event {
+        x,
+        kind,
+        wire,
+        segment,
+    } in events
+    {
+        let y = segment.start.y;
+
+        match kind {
+            EventKind::Insert => active.entry(y).or_default().push((wire, segment)),
+
+            EventKind::Query => {
+                let (lo, hi) = segment.y_range();
+
+                for (y, horizontals) in active.range(lo..=hi) {
+                    let pos = Point { x, y: *y };
+
+                    for (other_wire, other) in horizontals {
+                        if *other_wire != wire {
+                            intersections.push(Intersection {
+                                pos,
+                                wires: [
+                                    (wire, segment.steps_to(pos)),
+                                    (*other_wire, other.steps_to(pos)),
+                                ],
+                            });
+                        }
+                    }
+                }
+            }
+
+            EventKind::Remove => {
+                if let Some(horizontals) = active.get_mut(&y) {
+                    if let Some(idx) = horizontals
+                        .iter()
+                        .position(|entry| *entry == (wire, segment))
+                    {
+                        horizontals.swap_remove(idx);
+                    }
+
+                    if horizontals.is_empty() {
+                        active.remove(&y);
+                    }
+                }
+            }
+        }
+    }
+
+    intersections.retain(|intersection| intersection.pos != Point::zero());
+    intersections
+}
+
+/// Find the points shared by segments from different wires that run along the same line.
+fn overlaps(wires: &[Vec<Segment>], overlap: Overlap) -> Vec<Intersection> {
+    let mut lines = HashMap::<(bool, i32), Vec<(usize, Segment)>>::new();
+
+    for (wire, segments) in wires.iter().enumerate() {
+        for segment in segments {
+            let key = if segment.is_horizontal() {
+                (true, segment.start.y)
+            } else {
+                (false, segment.start.x)
+            };
+            lines.entry(key).or_default().push((wire, *segment));
+        }
+    }
+
+    let mut intersections = Vec::new();
+
+    for ((horizontal, coord), segments) in lines {
+        let range = |segment: &Segment| {
+            if horizontal {
+                segment.x_range()
+            } else {
+                segment.y_range()
+            }
+        };
+
+        for (idx, (wire, segment)) in segments.iter().enumerate() {
+            for (other_wire, other) in &segments[idx + 1..] {
+                if wire == other_wire {
+                    continue;
+                }
+
+                let (lo, hi) = range(segment);
+                let (other_lo, other_hi) = range(other);
+
+                let (lo, hi) = (lo.max(other_lo), hi.min(other_hi));
+                if lo > hi {
+                    continue;
+                }
+
+                let shared = match overlap {
+                    Overlap::Every => (lo..=hi).collect(),
+                    Overlap::Nearest => nearest_points(lo, hi, coord),
+                };
+
+                for n in shared {
+                    let pos = if horizontal {
+                        Point { x: n, y: coord }
+                    } else {
+                        Point { x: coord, y: n }
+                    };
+
+                    intersections.push(Intersection {
+                        pos,
+                        wires: [
+                            (*wire, segment.steps_to(pos)),
+                            (*other_wire, other.steps_to(pos)),
+                        ],
+                    });
+                }
+            }
+        }
+    }
+
+    intersections
+}
+
+/// The points of the stretch `lo..=hi` along the line at `coord` that can be closest to the origin.
+///
+/// Steps grow or shrink steadily along a stretch, so they are smallest at one of its ends. The
+/// Manhattan distance is smallest where the stretch comes nearest to zero. Since the origin itself
+/// doesn't count, its neighbours are candidates too when the line runs through it.
+fn nearest_points(lo: i32, hi: i32, coord: i32) -> Vec<i32> {
+    let mut points = vec![lo, hi, 0.clamp(lo, hi)];
+    if coord == 0 {
+        points.extend_from_slice(&[(-1).clamp(lo, hi), 1.clamp(lo, hi)]);
+    }
+
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+struct Event {
+    x: i32,
+    kind: EventKind,
+    wire: usize,
+    segment: Segment,
+}
+
+impl Event {
+    fn new(x: i32, kind: EventKind, wire: usize, segment: Segment) -> Self {
+        Self {
+            x,
+            kind,
+            wire,
+            segment,
+        }
+    }
+}
+
+/// The order of the variants matters. At the same x coordinate horizontal segments must be
+/// inserted before vertical segments query them, and only removed afterwards.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum EventKind {
+    Insert,
+    Query,
+    Remove,
+}
+
+#[test]
+fn segments_test() {
+    let wires = parse_wires("R8,U5,L5").unwrap();
+
+    assert_eq!(
+        segments(&wires[0]).unwrap(),
+        vec![
+            Segment {
+                start: Point { x: 0, y: 0 },
+                end: Point { x: 8, y: 0 },
+                steps: 0,
+            },
+            Segment {
+                start: Point { x: 8, y: 0 },
+                end: Point { x: 8, y: 5 },
+                steps: 8,
+            },
+            Segment {
+                start: Point { x: 8, y: 5 },
+                end: Point { x: 3, y: 5 },
+                steps: 13,
+            },
+        ]
+    );
+}
+
+#[test]
+fn intersections_test() {
+    let wires = parse_wires("R8,U5,L5,D3\nU7,R6,D4,L4")
+        .unwrap()
+        .iter()
+        .map(|moves| segments(moves).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut found = intersections(&wires)
+        .into_iter()
+        .map(|intersection| {
+            let [(_, a), (_, b)] = intersection.wires;
+            (intersection.pos, a + b)
+        })
+        .collect::<Vec<_>>();
+    found.sort_by_key(|(pos, _)| (pos.x, pos.y));
+
+    assert_eq!(
+        found,
+        vec![(Point { x: 3, y: 3 }, 40), (Point { x: 6, y: 5 }, 30)]
+    );
+}
+
+#[test]
+fn overlapping_segments_test() {
+    let wires = parse_wires("U2,R5\nR3,U2,R1")
+        .unwrap()
+        .iter()
+        .map(|moves| segments(moves).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut found = intersections(&wires)
+        .into_iter()
+        .map(|intersection| intersection.pos)
+        .collect::<Vec<_>>();
+    found.sort_by_key(|pos| (pos.x, pos.y));
+    found.dedup();
+
+    assert_eq!(found, vec![Point { x: 3, y: 2 }, Point { x: 4, y: 2 }]);
+}
+
+#[test]
+fn nearest_intersections_test() {
+    let wires = parse_wires("L5,R100\nR100")
+        .unwrap()
+        .iter()
+        .map(|moves| segments(moves).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut found = nearest_intersections(&wires)
+        .into_iter()
+        .map(|intersection| intersection.pos.x)
+        .collect::<Vec<_>>();
+    found.sort_unstable();
+    found.dedup();
+
+    assert_eq!(found, vec![1, 95]);
+}
+
+#[test]
+fn segments_overflow_test() {
+    let wires = parse_wires("R2147483647,R1\nD2147483647,D1\nR1").unwrap();
+
+    assert_eq!(
+        error_message(segments(&wires[0])),
+        "move 2 takes the wire more than 2147483647 steps"
+    );
+    assert_eq!(
+        error_message(segments(&wires[1])),
+        "move 2 takes the wire more than 2147483647 steps"
+    );
+    assert!(segments(&wires[2]).is_ok());
+}
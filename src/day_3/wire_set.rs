@@ -0,0 +1,188 @@
+use super::segment::{self, Intersection, Segment};
+use super::*;
+use std::collections::BTreeMap;
+
+/// Any number of wires laid out from the same origin.
+#[derive(Debug)]
+pub struct WireSet {
+    wires: Vec<Vec<Segment>>,
+}
+
+impl WireSet {
+    /// Parse one wire per line.
+    ///
+    /// Fails if the wires together are longer than an `i32` can count, since the combined steps
+    /// to a crossing could overflow otherwise.
+    pub fn parse(input: &str) -> Result<Self> {
+        let wires = parse_wires(input)?
+            .iter()
+            .enumerate()
+            .map(|(idx, moves)| {
+                segment::segments(moves)
+                    .map_err(|err| Error::msg(format!("wire {}: {}", idx + 1, err)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        wires
+            .iter()
+            .filter_map(|segments| segments.last())
+            .try_fold(0i32, |total, last| total.checked_add(last.length()))
+            .ok_or_else(|| {
+                Error::msg(format!(
+                    "the wires are more than {} steps long together",
+                    i32::MAX
+                ))
+            })?;
+
+        Ok(Self { wires })
+    }
+
+    /// Every point, other than the origin, where two or more different wires meet.
+    ///
+    /// Points where a wire only crosses itself are not included. Crossings are sorted by position.
+    pub fn crossings(&self) -> Vec<Crossing> {
+        merge(segment::intersections(&self.wires))
+    }
+
+    /// The crossing closest to the origin according to `metric`.
+    ///
+    /// Where wires run along each other only the few points that can be closest are considered,
+    /// so long overlaps cost no more than short ones.
+    pub fn closest(&self, metric: Metric) -> Option<Crossing> {
+        merge(segment::nearest_intersections(&self.wires))
+            .into_iter()
+            .min_by_key(|crossing| crossing.distance(metric))
+    }
+}
+
+/// Group intersections by position, keeping the first visit of each wire.
+fn merge(intersections: Vec<Intersection>) -> Vec<Crossing> {
+    let mut crossings = BTreeMap::<(i32, i32), BTreeMap<usize, i32>>::new();
+
+    for intersection in intersections {
+        let wires = crossings
+            .entry((intersection.pos.x, intersection.pos.y))
+            .or_default();
+
+        for (wire, steps) in &intersection.wires {
+            let first_visit = wires.entry(*wire).or_insert(*steps);
+            *first_visit = (*first_visit).min(*steps);
+        }
+    }
+
+    crossings
+        .into_iter()
+        .map(|((x, y), wires)| Crossing {
+            pos: Point { x, y },
+            wires: wires.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// A point where two or more wires meet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Crossing {
+    pub pos: Point,
+    /// The wires that pass through `pos`, sorted by their index in the `WireSet`, together with
+    /// the number of steps each wire takes to first reach `pos`.
+    pub wires: Vec<(usize, i32)>,
+}
+
+impl Crossing {
+    pub fn distance(&self, metric: Metric) -> i32 {
+        match metric {
+            Metric::Manhattan => manhattan_distance(Point::zero(), self.pos),
+            Metric::Steps => self.wires.iter().map(|(_, steps)| steps).sum(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Metric {
+    /// Manhattan distance from the origin.
+    Manhattan,
+    /// Combined steps taken by every wire meeting at the crossing.
+    Steps,
+}
+
+#[test]
+fn crossings_test() {
+    let wires = WireSet::parse("R8,U5,L5,D3\nU7,R6,D4,L4").unwrap();
+
+    assert_eq!(
+        wires.crossings(),
+        vec![
+            Crossing {
+                pos: Point { x: 3, y: 3 },
+                wires: vec![(0, 20), (1, 20)],
+            },
+            Crossing {
+                pos: Point { x: 6, y: 5 },
+                wires: vec![(0, 15), (1, 15)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn crossings_exclude_self_intersections_test() {
+    let wires = WireSet::parse("R4,U2,L2,D4\nU1,R5").unwrap();
+
+    assert_eq!(
+        wires.crossings(),
+        vec![
+            Crossing {
+                pos: Point { x: 2, y: 1 },
+                wires: vec![(0, 9), (1, 3)],
+            },
+            Crossing {
+                pos: Point { x: 4, y: 1 },
+                wires: vec![(0, 5), (1, 5)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_rejects_long_wires_test() {
+    assert_eq!(
+        error_message(WireSet::parse("R1\nR2147483647,R1\nU1")),
+        "wire 2: move 2 takes the wire more than 2147483647 steps"
+    );
+    assert_eq!(
+        error_message(WireSet::parse("R2147483647\nU1")),
+        "the wires are more than 2147483647 steps long together"
+    );
+}
+
+#[test]
+fn crossings_with_more_than_two_wires_test() {
+    let wires = WireSet::parse("R5\nU3,R2,D5\nD1,R3,U3").unwrap();
+
+    assert_eq!(
+        wires.crossings(),
+        vec![
+            Crossing {
+                pos: Point { x: 2, y: -1 },
+                wires: vec![(1, 9), (2, 3)],
+            },
+            Crossing {
+                pos: Point { x: 2, y: 0 },
+                wires: vec![(0, 2), (1, 8)],
+            },
+            Crossing {
+                pos: Point { x: 3, y: 0 },
+                wires: vec![(0, 3), (2, 5)],
+            },
+        ]
+    );
+
+    assert_eq!(
+        wires.closest(Metric::Manhattan).unwrap().pos,
+        Point { x: 2, y: 0 }
+    );
+    assert_eq!(
+        wires.closest(Metric::Steps).unwrap().pos,
+        Point { x: 3, y: 0 }
+    );
+}
@@ -1,23 +1,124 @@
-use super::{read_file, Error, Part, Result};
-use crate::BoolThenExt;
-use std::{collections::HashSet, ops::RangeBounds};
+#[cfg(test)]
+use super::error_message;
+use super::{read_input, Error, Opt, Part, Result};
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
 
+mod explain;
+// The original solutions, kept to check `RuleSet` against.
+#[cfg(test)]
 mod part_1;
+#[cfg(test)]
 mod part_2;
+mod rules;
 
-pub fn main(part: Part) -> Result<()> {
-    let input = (347312..=805915);
+pub use explain::Explanation;
+pub use rules::{DigitSum, Length, Monotonic, Rule, RuleSet, Run};
 
-    match part {
-        Part::One => part_1::main(input),
-        Part::Two => part_2::main(input),
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let rules = match (rules_from_opt(opt)?, part) {
+        (Some(rules), _) => rules,
+        (None, Part::One) => RuleSet::part_1(),
+        (None, Part::Two) => RuleSet::part_2(),
+    };
+
+    let input = parse_range(&read_input(opt, "input/day_4")?, &rules)?;
+
+    if opt.explain {
+        print!("{}", explain::Explanation::new(&rules, &input, 10));
+    } else {
+        println!("{}", rules.count(&input));
+    }
+
+    Ok(())
+}
+
+/// The number of passwords in the range `lo-hi` with two adjacent matching digits and digits that
+/// never decrease.
+pub fn part_1(input: &str) -> Result<usize> {
+    let rules = RuleSet::part_1();
+    Ok(rules.count(&parse_range(input, &rules)?))
+}
+
+/// Like `part_1`, but the matching digits must not be part of a longer run.
+pub fn part_2(input: &str) -> Result<usize> {
+    let rules = RuleSet::part_2();
+    Ok(rules.count(&parse_range(input, &rules)?))
+}
+
+/// Parse a range written as `lo-hi`, and check that it can contain passwords of the length
+/// `rules` require.
+pub fn parse_range(input: &str, rules: &RuleSet) -> Result<RangeInclusive<u64>> {
+    let input = input.trim();
+
+    let (lo, hi) = match input.split('-').collect::<Vec<_>>().as_slice() {
+        [lo, hi] => (lo.trim(), hi.trim()),
+        _ => {
+            return Err(Error::msg(format!(
+                "expected a range like 'lo-hi', got '{}'",
+                input
+            )))
+        }
+    };
+
+    let number = |s: &str| {
+        s.parse::<u64>()
+            .map_err(|_| Error::msg(format!("invalid number '{}' in range '{}'", s, input)))
+    };
+    let (lo, hi) = (number(lo)?, number(hi)?);
+
+    if lo > hi {
+        return Err(Error::msg(format!(
+            "range '{}' is empty, {} is greater than {}",
+            input, lo, hi
+        )));
+    }
+
+    if let Some(len) = rules.required_length() {
+        let largest = u32::try_from(len)
+            .ok()
+            .and_then(|len| 10_u64.checked_pow(len))
+            .map(|n| n - 1)
+            .ok_or_else(|| {
+                Error::msg(format!(
+                    "passwords can be at most 19 digits long, the rules want {}",
+                    len
+                ))
+            })?;
+        let smallest = (largest + 1) / 10;
+
+        if hi < smallest || lo > largest {
+            return Err(Error::msg(format!(
+                "range '{}' contains no {} digit numbers",
+                input, len
+            )));
+        }
+    }
+
+    Ok(lo..=hi)
+}
+
+/// Rules given with `--rule` or `--rules-file`, which replace the rules of the part.
+fn rules_from_opt(opt: &Opt) -> Result<Option<RuleSet>> {
+    let file = match &opt.rules_file {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => String::new(),
+    };
+
+    if file.is_empty() && opt.rule.is_empty() {
+        return Ok(None);
     }
+
+    let lines = file.lines().chain(opt.rule.iter().map(String::as_str));
+    RuleSet::parse(lines).map(Some)
 }
 
+#[cfg(test)]
 fn validate_length(n: u64, len: u64) -> Option<u64> {
-    (digit_length(n) == len).then(n)
+    (digit_length(n) == len).then_some(n)
 }
 
+#[cfg(test)]
 fn validate_two_identical_adjacent_digits(n: u64) -> Option<u64> {
     let digits = digits(n);
 
@@ -30,14 +131,15 @@ fn validate_two_identical_adjacent_digits(n: u64) -> Option<u64> {
                 .map(|next| next == digit)
                 .unwrap_or(false)
         })
-        .then(n)
+        .then_some(n)
 }
 
+#[cfg(test)]
 fn validate_two_identical_adjacent_digits_small_group(n: u64) -> Option<u64> {
     break_into_groups(n)
         .iter()
         .any(|group| group.len() == 2)
-        .then(n)
+        .then_some(n)
 }
 
 fn break_into_groups(n: u64) -> Vec<Vec<u64>> {
@@ -67,6 +169,7 @@ fn break_into_groups(n: u64) -> Vec<Vec<u64>> {
     groups
 }
 
+#[cfg(test)]
 fn validate_each_digit_increases(n: u64) -> Option<u64> {
     let digits = digits(n);
 
@@ -79,19 +182,121 @@ fn validate_each_digit_increases(n: u64) -> Option<u64> {
                 .map(|next| next >= digit)
                 .unwrap_or(true)
         })
-        .then(n)
+        .then_some(n)
 }
 
+#[cfg(test)]
 fn digit_length(n: u64) -> u64 {
     digits(n).len() as u64
 }
 
-fn digits(n: u64) -> Vec<u64> {
-    n.to_string()
-        .split("")
-        .filter(|digit| digit != &"")
-        .map(|digit| digit.parse::<u64>().unwrap())
-        .collect()
+fn digits(mut n: u64) -> Vec<u64> {
+    let mut digits = vec![n % 10];
+    n /= 10;
+
+    while n > 0 {
+        digits.push(n % 10);
+        n /= 10;
+    }
+
+    digits.reverse();
+    digits
+}
+
+/// Every `len` digit number in `range` whose digits never decrease from left to right, or
+/// `None` if `len` digit numbers don't all fit in a `u64`.
+///
+/// Rather than checking every number in the range this builds the numbers digit by digit, so
+/// only the few thousand candidates are ever looked at. Prefixes whose completions all fall
+/// outside the range are skipped.
+fn non_decreasing_numbers(range: &RangeInclusive<u64>, len: usize) -> Option<Vec<u64>> {
+    fn go(
+        prefix: u64,
+        min_digit: u64,
+        remaining: u32,
+        range: &RangeInclusive<u64>,
+        out: &mut Vec<u64>,
+    ) {
+        if remaining == 0 {
+            if range.contains(&prefix) {
+                out.push(prefix);
+            }
+            return;
+        }
+
+        let scale = 10_u64.pow(remaining);
+        let repunit = (scale - 1) / 9;
+        let smallest = prefix * scale + min_digit * repunit;
+        let largest = prefix * scale + (scale - 1);
+
+        if largest < *range.start() || smallest > *range.end() {
+            return;
+        }
+
+        for digit in min_digit..=9 {
+            go(prefix * 10 + digit, digit, remaining - 1, range, out);
+        }
+    }
+
+    // Every intermediate value in `go` is below 10^len, so checking it here is enough.
+    let len = u32::try_from(len).ok()?;
+    10_u64.checked_pow(len)?;
+
+    let mut out = vec![];
+    if len > 0 {
+        go(0, 1, len, range, &mut out);
+    }
+    Some(out)
+}
+
+#[test]
+fn parse_range_test() {
+    let rules = RuleSet::part_1();
+    assert_eq!(
+        parse_range("347312-805915\n", &rules).unwrap(),
+        347312..=805915
+    );
+    assert_eq!(parse_range("5-100000", &rules).unwrap(), 5..=100000);
+    assert_eq!(
+        parse_range("5-100", &RuleSet::new()).unwrap(),
+        5..=100,
+        "no length rule"
+    );
+
+    assert_eq!(
+        error_message(parse_range("347312", &rules)),
+        "expected a range like 'lo-hi', got '347312'"
+    );
+    assert_eq!(
+        error_message(parse_range("1-2-3", &rules)),
+        "expected a range like 'lo-hi', got '1-2-3'"
+    );
+    assert_eq!(
+        error_message(parse_range("1-x", &rules)),
+        "invalid number 'x' in range '1-x'"
+    );
+    assert_eq!(
+        error_message(parse_range("805915-347312", &rules)),
+        "range '805915-347312' is empty, 805915 is greater than 347312"
+    );
+    assert_eq!(
+        error_message(parse_range("5-99", &rules)),
+        "range '5-99' contains no 6 digit numbers"
+    );
+    assert_eq!(
+        error_message(parse_range("1000000-2000000", &rules)),
+        "range '1000000-2000000' contains no 6 digit numbers"
+    );
+
+    let long = |len| RuleSet::new().length(len);
+    assert_eq!(
+        error_message(parse_range("1-2", &long(19))),
+        "range '1-2' contains no 19 digit numbers"
+    );
+    assert_eq!(
+        error_message(parse_range("1-2", &long(20))),
+        "passwords can be at most 19 digits long, the rules want 20"
+    );
 }
 
 #[test]
@@ -101,6 +306,29 @@ fn digits_test() {
     assert_eq!(digits(102), vec![1, 0, 2], "102");
 }
 
+#[test]
+fn non_decreasing_numbers_test() {
+    assert_eq!(
+        non_decreasing_numbers(&(100..=125), 3),
+        Some(vec![
+            111, 112, 113, 114, 115, 116, 117, 118, 119, 122, 123, 124, 125
+        ])
+    );
+
+    let range = 347312..=805915;
+    let expected = range
+        .clone()
+        .filter(|n| validate_each_digit_increases(*n).is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(non_decreasing_numbers(&range, 6), Some(expected));
+
+    assert_eq!(
+        non_decreasing_numbers(&(0..=u64::MAX), 20),
+        None,
+        "10^20 doesn't fit in a u64"
+    );
+}
+
 #[test]
 fn validate_two_identical_adjacent_digits_test() {
     assert!(validate_two_identical_adjacent_digits(123).is_none());
@@ -115,7 +343,6 @@ fn validate_each_digit_increases_test() {
 }
 
 #[test]
-#[ignore]
 fn validate_two_identical_adjacent_digits_small_group_test() {
     assert!(
         validate_two_identical_adjacent_digits_small_group(112233).is_some(),
@@ -0,0 +1,173 @@
+use super::*;
+use rayon::prelude::*;
+use std::fmt;
+
+/// How a rule set treats every number in a range.
+pub struct Explanation {
+    range: RangeInclusive<u64>,
+    /// For each rule, in order, how many numbers it was the first to reject. Rules that imply
+    /// a looser one, like `run exactly 2`, come right after it, so the two are counted apart.
+    rejected_by: Vec<(String, usize)>,
+    matching: Vec<u64>,
+    sample_size: usize,
+}
+
+impl Explanation {
+    pub fn new(rules: &RuleSet, range: &RangeInclusive<u64>, sample_size: usize) -> Self {
+        let implied = rules.rules().map(Rule::implies).collect::<Vec<_>>();
+        let checks = rules
+            .rules()
+            .zip(&implied)
+            .flat_map(|(rule, implied)| implied.as_deref().into_iter().chain(Some(rule)))
+            .collect::<Vec<_>>();
+        let rule_count = checks.len();
+
+        let rejections = range
+            .clone()
+            .into_par_iter()
+            .fold(
+                || vec![0; rule_count],
+                |mut counts, n| {
+                    let digits = digits(n);
+                    if let Some(idx) = checks.iter().position(|rule| !rule.check(&digits)) {
+                        counts[idx] += 1;
+                    }
+                    counts
+                },
+            )
+            .reduce(
+                || vec![0; rule_count],
+                |mut a, b| {
+                    for (a, b) in a.iter_mut().zip(b) {
+                        *a += b;
+                    }
+                    a
+                },
+            );
+
+        let rejected_by = checks
+            .iter()
+            .map(|rule| rule.to_string())
+            .zip(rejections)
+            .collect();
+
+        Self {
+            range: range.clone(),
+            rejected_by,
+            matching: rules.matching(range),
+            sample_size,
+        }
+    }
+
+    pub fn candidates(&self) -> u64 {
+        self.range.end() - self.range.start() + 1
+    }
+
+    pub fn rejected_by(&self) -> &[(String, usize)] {
+        &self.rejected_by
+    }
+
+    /// Up to `sample_size` matching numbers spread evenly across all the matches.
+    pub fn sample(&self) -> Vec<u64> {
+        if self.sample_size == 0 {
+            return vec![];
+        }
+
+        let step = (self.matching.len() / self.sample_size).max(1);
+
+        self.matching
+            .iter()
+            .step_by(step)
+            .take(self.sample_size)
+            .copied()
+            .collect()
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}-{}: {} candidates, {} match",
+            self.range.start(),
+            self.range.end(),
+            self.candidates(),
+            self.matching.len()
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, "Rejected first by:")?;
+        let width = self
+            .rejected_by
+            .iter()
+            .map(|(rule, _)| rule.len())
+            .max()
+            .unwrap_or(0);
+        for (rule, count) in &self.rejected_by {
+            writeln!(f, "  {:width$}  {:>8}", rule, count, width = width)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Sample of matching numbers:")?;
+        for n in self.sample() {
+            let groups = break_into_groups(n)
+                .iter()
+                .map(|group| group.iter().map(|digit| digit.to_string()).collect())
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(f, "  {}  ({})", n, groups)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn explanation_test() {
+    let explanation = Explanation::new(&RuleSet::part_2(), &(111110..=111125), 3);
+
+    assert_eq!(explanation.candidates(), 16);
+    assert_eq!(
+        explanation.rejected_by(),
+        &[
+            ("length 6".to_string(), 0),
+            ("run at-least 2".to_string(), 0),
+            ("run exactly 2".to_string(), 15),
+            ("monotonic increasing".to_string(), 0),
+        ]
+    );
+    assert_eq!(explanation.sample(), vec![111122]);
+
+    assert_eq!(
+        explanation.to_string(),
+        "\
+111110-111125: 16 candidates, 1 match
+
+Rejected first by:
+  length 6                     0
+  run at-least 2               0
+  run exactly 2               15
+  monotonic increasing         0
+
+Sample of matching numbers:
+  111122  (1111 22)
+"
+    );
+}
+
+#[test]
+fn explanation_splits_runs_test() {
+    let explanation = Explanation::new(&RuleSet::part_2(), &(123440..=123459), 0);
+
+    // 123450 to 123459 have no adjacent pair at all except for 123455, while 123444 only has a
+    // group of three.
+    assert_eq!(
+        explanation.rejected_by(),
+        &[
+            ("length 6".to_string(), 0),
+            ("run at-least 2".to_string(), 9),
+            ("run exactly 2".to_string(), 1),
+            ("monotonic increasing".to_string(), 4),
+        ]
+    );
+}
@@ -1,15 +1,13 @@
 use super::*;
 use rayon::prelude::*;
 
-pub fn main<R>(input: R) -> Result<()>
-where
-    R: RangeBounds<u64> + IntoParallelIterator<Item = u64>,
-{
-    let count_matching = input.into_par_iter().filter_map(validate).count();
-
-    println!("{}", count_matching);
+fn count(input: &RangeInclusive<u64>) -> usize {
+    RuleSet::part_2().count(input)
+}
 
-    Ok(())
+/// Check every number in the range. Much slower than `count`, but kept around to check it against.
+fn count_brute_force(input: &RangeInclusive<u64>) -> usize {
+    input.clone().into_par_iter().filter_map(validate).count()
 }
 
 fn validate(n: u64) -> Option<u64> {
@@ -17,3 +15,35 @@ fn validate(n: u64) -> Option<u64> {
         .and_then(validate_two_identical_adjacent_digits_small_group)
         .and_then(validate_each_digit_increases)
 }
+
+#[test]
+fn count_test() {
+    let examples = vec![
+        ("347312-805915", 364),
+        ("100000-999999", 2046),
+        ("111111-111122", 1),
+        ("123444-123444", 0),
+        ("654321-654321", 0),
+    ];
+
+    for (input, expected) in examples {
+        let range = parse_range(input, &RuleSet::part_2()).unwrap();
+        assert_eq!(count(&range), expected, "{}", input);
+    }
+}
+
+#[test]
+fn count_brute_force_test() {
+    for range in &[347312..=805915, 111111..=111122, 654321..=654321, 5..=5] {
+        assert_eq!(count(range), count_brute_force(range), "{:?}", range);
+    }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn count_matches_brute_force(start in 0..1_000_000_u64, len in 0..5_000_u64) {
+        let range = start..=start + len;
+        proptest::prop_assert_eq!(count(&range), count_brute_force(&range), "{:?}", range);
+    }
+}
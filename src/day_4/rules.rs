@@ -0,0 +1,361 @@
+use super::*;
+#[cfg(test)]
+use crate::error_message;
+use rayon::prelude::*;
+use std::fmt;
+
+/// A condition a password has to satisfy. Rules look at the digits of the number, most
+/// significant first.
+pub trait Rule: fmt::Display + Send + Sync {
+    fn check(&self, digits: &[u64]) -> bool;
+
+    /// A looser rule that every number passing this one passes too. `--explain` checks it
+    /// first, to tell numbers that fail both apart from ones that only fail this rule.
+    fn implies(&self) -> Option<Box<dyn Rule>> {
+        None
+    }
+}
+
+/// The password has exactly this many digits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Length(pub usize);
+
+impl Rule for Length {
+    fn check(&self, digits: &[u64]) -> bool {
+        digits.len() == self.0
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "length {}", self.0)
+    }
+}
+
+/// Some run of identical adjacent digits has the given length.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Run {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Rule for Run {
+    fn check(&self, digits: &[u64]) -> bool {
+        runs(digits).any(|len| match self {
+            Run::Exactly(n) => len == *n,
+            Run::AtLeast(n) => len >= *n,
+        })
+    }
+
+    /// A run of exactly `n` digits is in particular a run of at least `n`.
+    fn implies(&self) -> Option<Box<dyn Rule>> {
+        match self {
+            Run::Exactly(n) => Some(Box::new(Run::AtLeast(*n))),
+            Run::AtLeast(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Run::Exactly(n) => write!(f, "run exactly {}", n),
+            Run::AtLeast(n) => write!(f, "run at-least {}", n),
+        }
+    }
+}
+
+fn runs(digits: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    let mut rest = digits;
+
+    std::iter::from_fn(move || {
+        let first = rest.first()?;
+        let len = rest.iter().take_while(|digit| *digit == first).count();
+        rest = &rest[len..];
+        Some(len)
+    })
+}
+
+/// Going from left to right the digits never decrease, or never increase.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Monotonic {
+    Increasing,
+    Decreasing,
+}
+
+impl Rule for Monotonic {
+    fn check(&self, digits: &[u64]) -> bool {
+        digits.windows(2).all(|pair| match self {
+            Monotonic::Increasing => pair[0] <= pair[1],
+            Monotonic::Decreasing => pair[0] >= pair[1],
+        })
+    }
+}
+
+impl fmt::Display for Monotonic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Monotonic::Increasing => write!(f, "monotonic increasing"),
+            Monotonic::Decreasing => write!(f, "monotonic decreasing"),
+        }
+    }
+}
+
+/// The sum of the digits is within `min..=max`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DigitSum {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Rule for DigitSum {
+    fn check(&self, digits: &[u64]) -> bool {
+        let sum = digits.iter().sum::<u64>();
+        self.min <= sum && sum <= self.max
+    }
+}
+
+impl fmt::Display for DigitSum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "digit-sum {}-{}", self.min, self.max)
+    }
+}
+
+/// A number of rules that must all hold.
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<Box<dyn Rule>>,
+    /// Required number of digits, if any rule says so. Used to count quickly.
+    length: Option<usize>,
+    increasing: bool,
+}
+
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn part_1() -> Self {
+        Self::new()
+            .length(6)
+            .with(Run::AtLeast(2))
+            .monotonic(Monotonic::Increasing)
+    }
+
+    pub fn part_2() -> Self {
+        Self::new()
+            .length(6)
+            .with(Run::Exactly(2))
+            .monotonic(Monotonic::Increasing)
+    }
+
+    pub fn with(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    pub fn length(mut self, len: usize) -> Self {
+        self.length = Some(len);
+        self.with(Length(len))
+    }
+
+    pub fn monotonic(mut self, order: Monotonic) -> Self {
+        if order == Monotonic::Increasing {
+            self.increasing = true;
+        }
+        self.with(order)
+    }
+
+    /// Parse one rule per line. Blank lines and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// length 6
+    /// run exactly 2
+    /// run at-least 2
+    /// monotonic increasing
+    /// monotonic decreasing
+    /// digit-sum 10-30
+    /// ```
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        lines
+            .into_iter()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .try_fold(Self::new(), |rules, line| {
+                let words = line.split_whitespace().collect::<Vec<_>>();
+                let number = |s: &str| {
+                    s.parse::<u64>().map_err(|_| {
+                        Error::msg(format!("invalid number '{}' in rule '{}'", s, line))
+                    })
+                };
+
+                let rules = match words.as_slice() {
+                    ["length", n] => rules.length(number(n)? as usize),
+                    ["run", "exactly", n] => rules.with(Run::Exactly(number(n)? as usize)),
+                    ["run", "at-least", n] => rules.with(Run::AtLeast(number(n)? as usize)),
+                    ["monotonic", "increasing"] => rules.monotonic(Monotonic::Increasing),
+                    ["monotonic", "decreasing"] => rules.monotonic(Monotonic::Decreasing),
+                    ["digit-sum", range] => {
+                        let mut parts = range.splitn(2, '-');
+                        let min = number(parts.next().unwrap_or(""))?;
+                        let max = number(parts.next().unwrap_or(""))?;
+                        if min > max {
+                            return Err(Error::msg(format!(
+                                "digit sum range in rule '{}' is empty, {} is greater than {}",
+                                line, min, max
+                            )));
+                        }
+                        rules.with(DigitSum { min, max })
+                    }
+                    _ => return Err(Error::msg(format!("unknown rule '{}'", line))),
+                };
+
+                Ok(rules)
+            })
+    }
+
+    /// The number of digits the rules require, if there is a length rule.
+    pub fn required_length(&self) -> Option<usize> {
+        self.length
+    }
+
+    pub fn check(&self, n: u64) -> bool {
+        self.first_failure(n).is_none()
+    }
+
+    /// The first rule, in the order they were added, that `n` doesn't satisfy.
+    pub fn first_failure(&self, n: u64) -> Option<&dyn Rule> {
+        self.first_failure_index(n).map(|idx| &*self.rules[idx])
+    }
+
+    /// Like `first_failure` but returns the position of the rule in `rules()`.
+    pub fn first_failure_index(&self, n: u64) -> Option<usize> {
+        let digits = digits(n);
+        self.rules.iter().position(|rule| !rule.check(&digits))
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        self.rules.iter().map(|rule| &**rule)
+    }
+
+    /// The numbers in `range` that satisfy every rule.
+    ///
+    /// If the rules require increasing digits and a fixed length of at most 19 digits, only the
+    /// numbers with non-decreasing digits are looked at. Otherwise every number in the range is
+    /// checked.
+    pub fn matching(&self, range: &RangeInclusive<u64>) -> Vec<u64> {
+        let candidates = match (self.length, self.increasing) {
+            (Some(len), true) => non_decreasing_numbers(range, len),
+            _ => None,
+        };
+
+        match candidates {
+            Some(candidates) => candidates.into_iter().filter(|n| self.check(*n)).collect(),
+            None => range
+                .clone()
+                .into_par_iter()
+                .filter(|n| self.check(*n))
+                .collect(),
+        }
+    }
+
+    pub fn count(&self, range: &RangeInclusive<u64>) -> usize {
+        self.matching(range).len()
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn run_test() {
+    let digits = digits(11122333);
+
+    assert_eq!(runs(&digits).collect::<Vec<_>>(), vec![3, 2, 3]);
+    assert!(Run::Exactly(2).check(&digits));
+    assert!(!Run::Exactly(1).check(&digits));
+    assert!(Run::AtLeast(3).check(&digits));
+    assert!(!Run::AtLeast(4).check(&digits));
+}
+
+#[test]
+fn monotonic_test() {
+    assert!(Monotonic::Increasing.check(&digits(1123)));
+    assert!(!Monotonic::Increasing.check(&digits(1132)));
+    assert!(Monotonic::Decreasing.check(&digits(9551)));
+    assert!(!Monotonic::Decreasing.check(&digits(9515)));
+}
+
+#[test]
+fn parse_test() {
+    let rules = RuleSet::parse(
+        "
+        # part 2 with a twist
+        length 6
+        run exactly 2
+        monotonic increasing
+        digit-sum 10-20
+        "
+        .lines(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        rules.to_string(),
+        "length 6\nrun exactly 2\nmonotonic increasing\ndigit-sum 10-20\n"
+    );
+    assert!(rules.check(112233));
+    assert!(!rules.check(112299));
+    assert_eq!(
+        rules.first_failure(123444).unwrap().to_string(),
+        "run exactly 2"
+    );
+
+    assert!(RuleSet::parse(vec!["length six"]).is_err());
+    assert!(RuleSet::parse(vec!["palindrome"]).is_err());
+    assert_eq!(
+        error_message(RuleSet::parse(vec!["digit-sum 30-10"])),
+        "digit sum range in rule 'digit-sum 30-10' is empty, 30 is greater than 10"
+    );
+}
+
+#[test]
+fn presets_test() {
+    let range = 347312..=805915;
+
+    assert_eq!(RuleSet::part_1().count(&range), 594);
+    assert_eq!(RuleSet::part_2().count(&range), 364);
+
+    let decreasing = RuleSet::new()
+        .length(3)
+        .with(Run::AtLeast(2))
+        .monotonic(Monotonic::Decreasing);
+    assert_eq!(
+        decreasing.matching(&(900..=999)),
+        vec![
+            900, 911, 922, 933, 944, 955, 966, 977, 988, 990, 991, 992, 993, 994, 995, 996, 997,
+            998, 999
+        ]
+    );
+}
+
+#[test]
+fn matching_long_lengths_test() {
+    // 10^20 doesn't fit in a u64, so these are checked one by one instead of built digit by
+    // digit.
+    let rules = RuleSet::new().length(20).monotonic(Monotonic::Increasing);
+    let start = 11_111_111_111_111_111_111;
+
+    assert_eq!(rules.count(&(start..=start + 100)), 45);
+    assert!(RuleSet::new()
+        .length(25)
+        .monotonic(Monotonic::Increasing)
+        .matching(&(u64::MAX - 100..=u64::MAX))
+        .is_empty());
+}
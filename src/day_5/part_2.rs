@@ -1,5 +1,5 @@
 use super::*;
 
-pub fn main(input: String) -> Result<()> {
+pub fn main(_input: String) -> Result<()> {
     Ok(())
 }
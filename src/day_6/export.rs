@@ -0,0 +1,188 @@
+use super::orbit_map::OrbitMap;
+use super::*;
+use std::fmt::Write;
+
+/// Draw the orbit map as a Graphviz digraph with an edge from each body to the bodies orbiting
+/// it. Every node is labelled with its depth, and the bodies and edges along `path` are drawn
+/// in red.
+pub fn dot(map: &OrbitMap, path: &[&str]) -> String {
+    let bodies = map.breadth_first();
+    let (on_path, path_edges) = path_sets(path);
+    let quote = |body: &str| format!("\"{}\"", escape(body));
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph orbits {{").unwrap();
+    writeln!(dot, "    rankdir=LR;").unwrap();
+
+    for (body, depth) in &bodies {
+        let style = if on_path.contains(body) {
+            ", color=red, fontcolor=red, penwidth=2"
+        } else {
+            ""
+        };
+        writeln!(
+            dot,
+            "    {} [label=\"{}\\n{}\"{}];",
+            quote(body),
+            escape(body),
+            depth,
+            style
+        )
+        .unwrap();
+    }
+
+    for (body, _) in &bodies {
+        for child in map.children(body) {
+            let style = if path_edges.contains(&(*body, *child)) {
+                " [color=red, penwidth=2]"
+            } else {
+                ""
+            };
+            writeln!(dot, "    {} -> {}{};", quote(body), quote(child), style).unwrap();
+        }
+    }
+
+    writeln!(dot, "}}").unwrap();
+    dot
+}
+
+/// Escape `body` for use inside a double quoted DOT string.
+fn escape(body: &str) -> String {
+    body.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Draw the orbit map as a Mermaid flowchart, with the same labels and highlighting as `dot`.
+pub fn mermaid(map: &OrbitMap, path: &[&str]) -> String {
+    let bodies = map.breadth_first();
+    let (on_path, path_edges) = path_sets(path);
+    let ids = bodies
+        .iter()
+        .enumerate()
+        .map(|(idx, (body, _))| (*body, format!("n{}", idx)))
+        .collect::<HashMap<_, _>>();
+
+    let mut mermaid = String::new();
+    writeln!(mermaid, "graph LR").unwrap();
+
+    for (body, depth) in &bodies {
+        writeln!(
+            mermaid,
+            "    {}[\"{} ({})\"]",
+            ids[body],
+            body.replace('"', "#quot;"),
+            depth
+        )
+        .unwrap();
+    }
+
+    let mut highlighted_links = vec![];
+    let mut link = 0;
+    for (body, _) in &bodies {
+        for child in map.children(body) {
+            writeln!(mermaid, "    {} --> {}", ids[body], ids[child]).unwrap();
+            if path_edges.contains(&(*body, *child)) {
+                highlighted_links.push(link.to_string());
+            }
+            link += 1;
+        }
+    }
+
+    if !on_path.is_empty() {
+        let nodes = bodies
+            .iter()
+            .filter(|(body, _)| on_path.contains(body))
+            .map(|(body, _)| ids[body].as_str())
+            .collect::<Vec<_>>();
+
+        writeln!(mermaid, "    classDef path stroke:#f00,stroke-width:3px").unwrap();
+        writeln!(mermaid, "    class {} path", nodes.join(",")).unwrap();
+    }
+
+    if !highlighted_links.is_empty() {
+        writeln!(
+            mermaid,
+            "    linkStyle {} stroke:#f00,stroke-width:3px",
+            highlighted_links.join(",")
+        )
+        .unwrap();
+    }
+
+    mermaid
+}
+
+/// The bodies on `path`, and its edges as `(parent, child)` pairs.
+fn path_sets<'a>(path: &[&'a str]) -> (HashSet<&'a str>, HashSet<(&'a str, &'a str)>) {
+    let bodies = path.iter().copied().collect();
+    let edges = path
+        .windows(2)
+        .flat_map(|pair| vec![(pair[0], pair[1]), (pair[1], pair[0])])
+        .collect();
+    (bodies, edges)
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "COM)B\nB)C\nC)D\nB)G";
+
+#[test]
+fn dot_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+
+    assert_eq!(
+        dot(&map, &["G", "B", "C"]),
+        r#"digraph orbits {
+    rankdir=LR;
+    "COM" [label="COM\n0"];
+    "B" [label="B\n1", color=red, fontcolor=red, penwidth=2];
+    "C" [label="C\n2", color=red, fontcolor=red, penwidth=2];
+    "G" [label="G\n2", color=red, fontcolor=red, penwidth=2];
+    "D" [label="D\n3"];
+    "COM" -> "B";
+    "B" -> "C" [color=red, penwidth=2];
+    "B" -> "G" [color=red, penwidth=2];
+    "C" -> "D";
+}
+"#
+    );
+}
+
+#[test]
+fn dot_escapes_names_test() {
+    let map = OrbitMap::parse("COM)a\"b\na\"b)c\\d").unwrap();
+
+    assert_eq!(
+        dot(&map, &[]),
+        r#"digraph orbits {
+    rankdir=LR;
+    "COM" [label="COM\n0"];
+    "a\"b" [label="a\"b\n1"];
+    "c\\d" [label="c\\d\n2"];
+    "COM" -> "a\"b";
+    "a\"b" -> "c\\d";
+}
+"#
+    );
+}
+
+#[test]
+fn mermaid_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+
+    assert_eq!(
+        mermaid(&map, &[]),
+        r#"graph LR
+    n0["COM (0)"]
+    n1["B (1)"]
+    n2["C (2)"]
+    n3["G (2)"]
+    n4["D (3)"]
+    n0 --> n1
+    n1 --> n2
+    n1 --> n3
+    n2 --> n4
+"#
+    );
+
+    assert!(mermaid(&map, &["C", "D"]).ends_with(
+        "    classDef path stroke:#f00,stroke-width:3px\n    class n2,n4 path\n    linkStyle 3 stroke:#f00,stroke-width:3px\n"
+    ));
+}
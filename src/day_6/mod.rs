@@ -1,82 +1,88 @@
-use super::{read_file, Error, Part, Result};
+#[cfg(test)]
+use super::error_message;
+use super::{read_input, Opt, Part, Result};
 use anyhow::format_err;
-use std::collections::{HashMap, HashSet};
-use std::convert::TryFrom;
-use std::fmt;
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
+mod export;
+mod orbit_map;
 mod part_1;
 
-pub fn main(part: Part) -> Result<()> {
-    let input = read_file("input/day_6")?;
+pub use orbit_map::{OrbitMap, COM};
+pub use part_1::count_orbits;
+
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let input = read_input(opt, "input/day_6")?;
+
+    if let Some(path) = &opt.render {
+        return render(&input, path, opt);
+    }
 
     match part {
-        Part::One => part_1::main(&input)?,
+        Part::One => println!("{}", part_1(&input)?),
+        Part::Two if opt.from.is_some() || opt.to.is_some() => {
+            let map = OrbitMap::parse(&input)?;
+            let from = opt.from.as_deref().unwrap_or("YOU");
+            let to = opt.to.as_deref().unwrap_or("SAN");
+
+            let path = orbital_transfers(&map, from, to)?;
+            println!("{}", path.len() - 1);
+            println!("{}", path.join(" -> "));
+        }
         Part::Two => println!("{}", part_2(&input)?),
     }
 
     Ok(())
 }
 
-fn part_2(input: &str) -> Result<u64> {
-    let (map, orbiter_to_planet) = parse_input(&input);
-
-    let start = orbiter_to_planet["YOU"];
-    let end = orbiter_to_planet["SAN"];
-
-    let path = shortest_path(&map, &mut HashSet::new(), start, end)
-        .ok_or_else(|| format_err!("no path"))?;
-
-    Ok(path)
+/// Export the orbit map to `path`, as Mermaid if it ends in `.mmd` and otherwise as DOT. The
+/// transfer path is highlighted if `--from` or `--to` is given.
+fn render(input: &str, path: &Path, opt: &Opt) -> Result<()> {
+    let map = OrbitMap::parse(input)?;
+
+    let transfer_path = if opt.from.is_some() || opt.to.is_some() {
+        let from = opt.from.as_deref().unwrap_or("YOU");
+        let to = opt.to.as_deref().unwrap_or("SAN");
+        orbital_transfers(&map, from, to)?
+    } else {
+        vec![]
+    };
+
+    let output = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("mmd") => export::mermaid(&map, &transfer_path),
+        _ => export::dot(&map, &transfer_path),
+    };
+
+    std::fs::write(path, output).map_err(From::from)
 }
 
-fn shortest_path<'a>(
-    map: &HashMap<&str, Vec<&'a str>>,
-    visited: &mut HashSet<&'a str>,
-    start: &str,
-    end: &str,
-) -> Option<u64> {
-    if start == end {
-        return Some(0);
-    }
-
-    map.get(start).and_then(|orbiters| {
-        let orbiters = orbiters
-            .iter()
-            .filter(|orbiter| !visited.contains(*orbiter))
-            .collect::<Vec<_>>();
-
-        for orbiter in &orbiters {
-            visited.insert(orbiter);
-        }
-
-        orbiters
-            .iter()
-            .filter_map(|orbiter| shortest_path(map, visited, *orbiter, end).map(|len| len + 1))
-            .min()
-    })
+/// The total number of direct and indirect orbits in the map.
+pub fn part_1(input: &str) -> Result<usize> {
+    Ok(count_orbits(&OrbitMap::parse(input)?))
 }
 
-fn parse_input(input: &str) -> (HashMap<&str, Vec<&str>>, HashMap<&str, &str>) {
-    let mut map = HashMap::new();
-    let mut orbiter_to_planet = HashMap::new();
-
-    for line in input.lines() {
-        let parts = parse_line(line);
-
-        map.entry(parts.0).or_insert(vec![]).push(parts.1);
-
-        map.entry(parts.1).or_insert(vec![]).push(parts.0);
-
-        orbiter_to_planet.insert(parts.1, parts.0);
-    }
-
-    (map, orbiter_to_planet)
+/// The number of orbital transfers needed to get from the body YOU orbit to the body SAN orbits.
+pub fn part_2(input: &str) -> Result<u64> {
+    let map = OrbitMap::parse(input)?;
+    let path = orbital_transfers(&map, "YOU", "SAN")?;
+    Ok(path.len() as u64 - 1)
 }
 
-fn parse_line(line: &str) -> (&str, &str) {
-    let parts = line.split(")").collect::<Vec<_>>();
-    (&parts[0], &parts[1])
+/// The bodies visited when moving `from` from the body it orbits to the body `to` orbits.
+///
+/// The number of orbital transfers needed is one less than the length of the path.
+pub fn orbital_transfers<'a>(map: &OrbitMap<'a>, from: &str, to: &str) -> Result<Vec<&'a str>> {
+    let orbiting = |body: &str| {
+        if !map.contains(body) {
+            Err(format_err!("there is no body named '{}'", body))
+        } else {
+            map.parent(body)
+                .ok_or_else(|| format_err!("{} isn't orbiting anything", body))
+        }
+    };
+
+    map.path(orbiting(from)?, orbiting(to)?)
 }
 
 #[test]
@@ -88,3 +94,43 @@ fn part_2_test() {
     .join("\n");
     assert_eq!(4, part_2(&input).unwrap());
 }
+
+#[test]
+fn orbital_transfers_test() {
+    let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN";
+    let map = OrbitMap::parse(input).unwrap();
+
+    assert_eq!(
+        orbital_transfers(&map, "YOU", "SAN").unwrap(),
+        vec!["K", "J", "E", "D", "I"]
+    );
+    assert_eq!(
+        orbital_transfers(&map, "H", "F").unwrap(),
+        vec!["G", "B", "C", "D", "E"]
+    );
+
+    assert_eq!(
+        error_message(orbital_transfers(&map, "YOU", "SANTA")),
+        "there is no body named 'SANTA'"
+    );
+    assert_eq!(
+        error_message(orbital_transfers(&map, "COM", "SAN")),
+        "COM isn't orbiting anything"
+    );
+}
+
+/// A single chain `COM)B1`, `B1)B2`, ... with `YOU` and `SAN` orbiting near either end.
+#[cfg(test)]
+fn deep_chain(len: usize) -> String {
+    let mut lines = vec!["COM)B1".to_string()];
+    lines.extend((2..=len).map(|n| format!("B{})B{}", n - 1, n)));
+    lines.push("B2)YOU".to_string());
+    lines.push(format!("B{})SAN", len));
+    lines.join("\n")
+}
+
+#[test]
+fn part_2_deep_chain_test() {
+    let len = 200_000;
+    assert_eq!(part_2(&deep_chain(len)).unwrap(), len as u64 - 2);
+}
@@ -0,0 +1,357 @@
+use super::*;
+#[cfg(test)]
+use crate::error_message;
+
+/// The body everything else orbits, directly or indirectly.
+pub const COM: &str = "COM";
+
+/// A validated tree of which body orbits which, rooted at `COM`.
+#[derive(Debug)]
+pub struct OrbitMap<'a> {
+    parents: HashMap<&'a str, &'a str>,
+    children: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> OrbitMap<'a> {
+    /// Parse lines like `A)B`, meaning `B` orbits `A`.
+    ///
+    /// Fails if a line is malformed, if a body orbits two different bodies, if the orbits form a
+    /// cycle, or if a body isn't connected to `COM`. Errors name the offending line, counting
+    /// from 1.
+    pub fn parse(input: &'a str) -> Result<Self> {
+        let mut parents = HashMap::new();
+        let mut children = HashMap::<&str, Vec<&str>>::new();
+        let mut lines = Vec::new();
+
+        for (idx, line) in input.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (parent, child) = parse_line(line).ok_or_else(|| {
+                format_err!("line {}: expected 'A)B', got '{}'", line_number, line)
+            })?;
+
+            if child == COM {
+                return Err(format_err!(
+                    "line {}: {} can't orbit anything",
+                    line_number,
+                    COM
+                ));
+            }
+
+            if let Some(other_parent) = parents.insert(child, parent) {
+                return Err(format_err!(
+                    "line {}: {} orbits both {} and {}",
+                    line_number,
+                    child,
+                    other_parent,
+                    parent
+                ));
+            }
+
+            children.entry(parent).or_default().push(child);
+            lines.push((line_number, line, child));
+        }
+
+        let map = Self { parents, children };
+        map.check_connected(&lines)?;
+        Ok(map)
+    }
+
+    /// Make sure every body can be reached by walking outwards from `COM`.
+    fn check_connected(&self, lines: &[(usize, &str, &'a str)]) -> Result<()> {
+        let mut reachable = HashSet::new();
+        let mut queue = vec![COM];
+
+        while let Some(body) = queue.pop() {
+            reachable.insert(body);
+            queue.extend(self.children(body));
+        }
+
+        for (line_number, line, child) in lines {
+            if reachable.contains(child) {
+                continue;
+            }
+
+            // Walking inwards from a body that can't be reached from COM either ends at some
+            // other root, or goes around in a cycle.
+            let mut seen = HashSet::new();
+            let mut body = *child;
+            while let Some(parent) = self.parent(body) {
+                if !seen.insert(body) {
+                    return Err(format_err!(
+                        "line {}: {} is part of an orbit cycle",
+                        line_number,
+                        line
+                    ));
+                }
+                body = parent;
+            }
+
+            return Err(format_err!(
+                "line {}: {} is not connected to {}",
+                line_number,
+                line,
+                COM
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn contains(&self, body: &str) -> bool {
+        body == COM || self.parents.contains_key(body)
+    }
+
+    /// Every body in the map except `COM`.
+    pub fn bodies(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.parents.keys().copied()
+    }
+
+    /// The body `body` directly orbits. `None` for `COM` and bodies not in the map.
+    pub fn parent(&self, body: &str) -> Option<&'a str> {
+        self.parents.get(body).copied()
+    }
+
+    /// The bodies directly orbiting `body`.
+    pub fn children(&self, body: &str) -> &[&'a str] {
+        self.children
+            .get(body)
+            .map(|children| children.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The bodies `body` orbits indirectly, starting with its parent and ending with `COM`.
+    pub fn ancestors(&self, body: &str) -> impl Iterator<Item = &'a str> + '_ {
+        std::iter::successors(self.parent(body), move |body| self.parent(body))
+    }
+
+    /// The number of direct and indirect orbits of `body`, so 0 for `COM`.
+    pub fn depth(&self, body: &str) -> Option<usize> {
+        if self.contains(body) {
+            Some(self.ancestors(body).count())
+        } else {
+            None
+        }
+    }
+
+    /// The depth of every body including `COM`, computed in a single breadth first walk.
+    pub fn depths(&self) -> HashMap<&'a str, usize> {
+        self.breadth_first().into_iter().collect()
+    }
+
+    /// Every body and its depth, walking outwards from `COM` one level at a time. Children are
+    /// visited in the order they appeared in the input.
+    pub fn breadth_first(&self) -> Vec<(&'a str, usize)> {
+        let mut bodies = Vec::with_capacity(self.parents.len() + 1);
+        let mut queue = VecDeque::new();
+        queue.push_back((COM, 0));
+
+        while let Some((body, depth)) = queue.pop_front() {
+            bodies.push((body, depth));
+            queue.extend(self.children(body).iter().map(|child| (*child, depth + 1)));
+        }
+
+        bodies
+    }
+
+    /// The bodies on the path from `from` to `to`, including both ends. Fails if either body
+    /// isn't in the map.
+    ///
+    /// The path always goes through the lowest common ancestor of the two bodies.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<&'a str>> {
+        let from = self.body(from)?;
+        let to = self.body(to)?;
+
+        let from_ancestors = std::iter::once(from)
+            .chain(self.ancestors(from))
+            .collect::<Vec<_>>();
+        let from_distances = from_ancestors
+            .iter()
+            .enumerate()
+            .map(|(distance, body)| (*body, distance))
+            .collect::<HashMap<_, _>>();
+
+        let mut to_ancestors = vec![];
+        for body in std::iter::once(to).chain(self.ancestors(to)) {
+            if let Some(distance) = from_distances.get(body) {
+                let mut path = from_ancestors[..=*distance].to_vec();
+                path.extend(to_ancestors.into_iter().rev());
+                return Ok(path);
+            }
+            to_ancestors.push(body);
+        }
+
+        unreachable!("every body in a validated map has {} as an ancestor", COM)
+    }
+
+    /// The number of orbital transfers needed to get from `from` to `to`, that is the number of
+    /// edges on the path between them.
+    pub fn transfers(&self, from: &str, to: &str) -> Result<usize> {
+        self.path(from, to).map(|path| path.len() - 1)
+    }
+
+    /// The name of `body` as stored in the map, or an error if it isn't in the map.
+    fn body(&self, body: &str) -> Result<&'a str> {
+        if body == COM {
+            return Ok(COM);
+        }
+
+        self.parents
+            .get_key_value(body)
+            .map(|(body, _)| *body)
+            .ok_or_else(|| format_err!("there is no body named '{}'", body))
+    }
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.split(')');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(parent), Some(child), None) if !parent.is_empty() && !child.is_empty() => {
+            Some((parent, child))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+
+#[test]
+fn queries_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+
+    assert_eq!(map.parent("D"), Some("C"));
+    assert_eq!(map.parent(COM), None);
+    assert_eq!(map.children("B"), &["C", "G"]);
+    assert!(map.children("L").is_empty());
+    assert_eq!(map.depth(COM), Some(0));
+    assert_eq!(map.depth("L"), Some(7));
+    assert_eq!(map.depth("X"), None);
+    assert_eq!(map.ancestors("H").collect::<Vec<_>>(), vec!["G", "B", COM]);
+    assert_eq!(map.bodies().count(), 11);
+}
+
+#[test]
+fn depths_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+    let depths = map.depths();
+
+    assert_eq!(depths.len(), 12);
+    assert_eq!(depths[COM], 0);
+    assert_eq!(depths["L"], 7);
+    assert_eq!(depths.values().sum::<usize>(), 42);
+}
+
+#[test]
+fn transfers_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+
+    assert_eq!(map.transfers("K", "I").unwrap(), 4);
+    assert_eq!(map.transfers("I", "K").unwrap(), 4);
+    assert_eq!(map.transfers("L", COM).unwrap(), 7);
+    assert_eq!(map.transfers("H", "H").unwrap(), 0);
+    assert_eq!(
+        error_message(map.transfers("H", "X")),
+        "there is no body named 'X'"
+    );
+}
+
+#[test]
+fn path_test() {
+    let map = OrbitMap::parse(EXAMPLE).unwrap();
+
+    assert_eq!(map.path("K", "I").unwrap(), vec!["K", "J", "E", "D", "I"]);
+    assert_eq!(map.path("I", "K").unwrap(), vec!["I", "D", "E", "J", "K"]);
+    assert_eq!(map.path("D", "L").unwrap(), vec!["D", "E", "J", "K", "L"]);
+    assert_eq!(map.path("C", COM).unwrap(), vec!["C", "B", COM]);
+    assert_eq!(map.path("H", "H").unwrap(), vec!["H"]);
+}
+
+#[test]
+fn parse_errors_test() {
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nB-C")),
+        "line 2: expected 'A)B', got 'B-C'"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nB)")),
+        "line 2: expected 'A)B', got 'B)'"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nB)C)D")),
+        "line 2: expected 'A)B', got 'B)C)D'"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nCOM)C\nB)D\nC)D")),
+        "line 4: D orbits both B and C"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nX)Y\nY)X")),
+        "line 2: X)Y is part of an orbit cycle"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nB)COM")),
+        "line 2: COM can't orbit anything"
+    );
+    assert_eq!(
+        error_message(OrbitMap::parse("COM)B\nX)Y\nY)Z")),
+        "line 2: X)Y is not connected to COM"
+    );
+}
+
+#[test]
+fn parse_ignores_blank_lines_and_crlf_test() {
+    let map = OrbitMap::parse("COM)B\r\n\r\nB)C\r\n").unwrap();
+
+    assert_eq!(map.depth("C"), Some(2));
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn name(i: usize) -> String {
+        if i == 0 {
+            COM.to_string()
+        } else {
+            format!("B{}", i)
+        }
+    }
+
+    /// The lines of a random tree. Body `i` orbits a body before it, so it's always connected
+    /// to `COM` without cycles.
+    fn tree() -> impl Strategy<Value = String> {
+        (1..40_usize)
+            .prop_flat_map(|len| (1..=len).map(|i| 0..i).collect::<Vec<_>>())
+            .prop_map(|parents| {
+                parents
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, parent)| format!("{}){}", name(*parent), name(idx + 1)))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn transfers_are_symmetric(input in tree(), a in 0..40_usize, b in 0..40_usize) {
+            let map = OrbitMap::parse(&input).unwrap();
+            let len = map.bodies().count() + 1;
+            let (a, b) = (name(a % len), name(b % len));
+
+            prop_assert_eq!(map.transfers(&a, &b).unwrap(), map.transfers(&b, &a).unwrap());
+
+            let mut path = map.path(&b, &a).unwrap();
+            path.reverse();
+            prop_assert_eq!(map.path(&a, &b).unwrap(), path);
+        }
+    }
+}
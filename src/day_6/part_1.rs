@@ -1,40 +1,24 @@
-use super::{read_file, Error, Part, Result};
-use std::collections::{HashMap, HashSet};
-use std::convert::TryFrom;
-use std::fmt;
-use std::str::FromStr;
+use super::orbit_map::OrbitMap;
 
-pub fn main(input: &str) -> Result<()> {
-    let map = parse_input(&input);
-    let mut count = 0;
-    count_orbits(&map, "COM", &mut count, 0);
-    println!("{}", count);
-
-    Ok(())
-}
-
-fn count_orbits(map: &HashMap<&str, Vec<&str>>, planet: &str, count: &mut u64, depth: u64) {
-    if let Some(orbiters) = map.get(planet) {
-        for orbiter in orbiters {
-            *count += 1;
-            *count += depth;
-            count_orbits(map, orbiter, count, depth + 1);
-        }
-    }
+/// The total number of direct and indirect orbits, which is the sum of the depths of all bodies.
+pub fn count_orbits(map: &OrbitMap) -> usize {
+    map.depths().values().sum()
 }
 
-fn parse_input(input: &str) -> HashMap<&str, Vec<&str>> {
-    let mut map = HashMap::new();
+#[test]
+fn count_orbits_test() {
+    let input = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L";
+    let map = OrbitMap::parse(input).unwrap();
 
-    for line in input.lines() {
-        let parts = parse_line(line);
-        map.entry(parts.0).or_insert(vec![]).push(parts.1);
-    }
-
-    map
+    assert_eq!(count_orbits(&map), 42);
 }
 
-fn parse_line(line: &str) -> (&str, &str) {
-    let parts = line.split(")").collect::<Vec<_>>();
-    (&parts[0], &parts[1])
+#[test]
+fn count_orbits_deep_chain_test() {
+    let len = 200_000;
+    let input = super::deep_chain(len);
+    let map = OrbitMap::parse(&input).unwrap();
+
+    // The chain itself contributes 1 + 2 + ... + len, YOU orbits B2 and SAN orbits the last body.
+    assert_eq!(count_orbits(&map), len * (len + 1) / 2 + 3 + (len + 1));
 }
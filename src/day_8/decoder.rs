@@ -0,0 +1,165 @@
+use super::*;
+use std::io::BufRead;
+
+/// The digit of a pixel that lets the layers behind it show through.
+const TRANSPARENT: u8 = 2;
+
+/// Decodes an image one digit at a time, keeping only the current layer's histogram and the
+/// image composed so far. Memory use depends on the size of a layer, not on the number of
+/// layers.
+pub struct Decoder {
+    size: Size,
+    /// The front-most digit that isn't transparent at each position, row by row.
+    image: Vec<u8>,
+    histogram: Histogram,
+    /// How many digits have been pushed in total.
+    digits: usize,
+}
+
+impl Decoder {
+    pub fn new(size: Size) -> Result<Self> {
+        size.check()?;
+
+        Ok(Self {
+            size,
+            image: vec![TRANSPARENT; size.area()],
+            histogram: Histogram::default(),
+            digits: 0,
+        })
+    }
+
+    /// Add the next digit. Returns the histogram of the layer if this was its last digit.
+    pub fn push(&mut self, digit: u8) -> Option<Histogram> {
+        let position = self.digits % self.size.area();
+
+        if self.image[position] == TRANSPARENT {
+            self.image[position] = digit;
+        }
+        self.histogram.add(digit);
+        self.digits += 1;
+
+        if position + 1 == self.size.area() {
+            Some(std::mem::take(&mut self.histogram))
+        } else {
+            None
+        }
+    }
+
+    /// The composed image. Fails if no digits were pushed, or the last layer is incomplete.
+    pub fn finish(self) -> Result<Grid<u8>> {
+        if self.digits == 0 {
+            return Err(anyhow!("no image data"));
+        }
+
+        if !self.digits.is_multiple_of(self.size.area()) {
+            return Err(self.size.partial_layer_error(self.digits));
+        }
+
+        Grid::from_vec(self.size.width, self.size.height, self.image)
+    }
+}
+
+/// Read the digits of an image from `reader` in a single pass. `on_layer` is called with the
+/// histogram of every layer, front to back, and the composed image is returned at the end.
+///
+/// Line breaks are skipped, so a trailing newline is fine.
+pub fn decode(
+    reader: impl BufRead,
+    size: Size,
+    mut on_layer: impl FnMut(Histogram),
+) -> Result<Grid<u8>> {
+    let mut decoder = Decoder::new(size)?;
+
+    for byte in reader.bytes() {
+        let byte = byte?;
+
+        if byte == b'\n' || byte == b'\r' {
+            continue;
+        }
+
+        if let Some(histogram) = decoder.push(parse_char(byte as char)?) {
+            on_layer(histogram);
+        }
+    }
+
+    decoder.finish()
+}
+
+#[test]
+fn decode_test() {
+    let size = Size {
+        width: 3,
+        height: 2,
+    };
+    let mut histograms = vec![];
+    let image = decode("123456789012\n".as_bytes(), size, |histogram| {
+        histograms.push(histogram)
+    })
+    .unwrap();
+
+    assert_eq!(histograms.len(), 2);
+    assert_eq!(
+        (0..10)
+            .map(|digit| histograms[0].count(digit))
+            .collect::<Vec<_>>(),
+        vec![0, 1, 1, 1, 1, 1, 1, 0, 0, 0]
+    );
+    assert_eq!(
+        (0..10)
+            .map(|digit| histograms[1].count(digit))
+            .collect::<Vec<_>>(),
+        vec![1, 1, 1, 0, 0, 0, 0, 1, 1, 1]
+    );
+    assert_eq!(
+        image,
+        Grid::from_rows(vec![vec![1, 8, 3], vec![4, 5, 6]]).unwrap()
+    );
+}
+
+#[test]
+fn decode_matches_compose_test() {
+    let input = read_file("input/day_8").unwrap();
+    let layers = build_layers(input.trim_end(), DEFAULT_SIZE).unwrap();
+
+    let mut histograms = vec![];
+    let image = decode(input.as_bytes(), DEFAULT_SIZE, |histogram| {
+        histograms.push(histogram)
+    })
+    .unwrap();
+
+    assert_eq!(
+        histograms,
+        layers.iter().map(Histogram::of).collect::<Vec<_>>()
+    );
+    assert_eq!(to_image(&image), compose(&layers, DEFAULT_SIZE));
+}
+
+#[test]
+fn decode_errors_test() {
+    let size = |width, height| Size { width, height };
+
+    assert_eq!(
+        error_message(decode("12345678901".as_bytes(), size(3, 2), |_| {})),
+        "11 digits don't fit into whole 3x2 layers of 6 digits each"
+    );
+    assert_eq!(
+        error_message(decode("\n".as_bytes(), size(3, 2), |_| {})),
+        "no image data"
+    );
+    assert_eq!(
+        error_message(decode("1234".as_bytes(), size(2, 0), |_| {})),
+        "image size must be at least 1x1, got 2x0"
+    );
+    assert_eq!(
+        error_message(decode("1234".as_bytes(), size(usize::MAX, 2), |_| {})),
+        format!(
+            "image size {}x2 is too large, a layer would have more than {} digits",
+            usize::MAX,
+            usize::MAX
+        )
+    );
+    assert_eq!(
+        error_message(decode("12a4".as_bytes(), size(2, 2), |_| {})),
+        "Invalid digit: a"
+    );
+}
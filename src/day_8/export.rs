@@ -0,0 +1,133 @@
+use super::*;
+use std::fs;
+use std::path::Path;
+
+/// Write `image` to `path` with every pixel drawn as a `scale` by `scale` square. The format is
+/// picked from the extension, which has to be `png` or `ppm`.
+pub fn export(image: &Image, path: &Path, scale: usize, palette: &Palette) -> Result<()> {
+    let bytes = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => png(image, scale, palette)?,
+        Some("ppm") => ppm(image, scale, palette),
+        _ => {
+            return Err(anyhow!(
+                "don't know how to export {}, expected a .png or .ppm file",
+                path.display()
+            ))
+        }
+    };
+
+    fs::write(path, bytes).map_err(|err| anyhow!("failed to write {}: {}", path.display(), err))
+}
+
+/// Write every layer to `dir` as `layer_000.png`, `layer_001.png` and so on, creating `dir` if
+/// it doesn't exist.
+pub fn dump_layers(layers: &[Layer], dir: &Path, scale: usize, palette: &Palette) -> Result<()> {
+    fs::create_dir_all(dir)
+        .map_err(|err| anyhow!("failed to create {}: {}", dir.display(), err))?;
+
+    for (idx, layer) in layers.iter().enumerate() {
+        export(
+            &to_image(layer),
+            &dir.join(format!("layer_{:03}.png", idx)),
+            scale,
+            palette,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The RGB bytes of `image` scaled up by `scale`, row by row. Transparent pixels are drawn as a
+/// checkerboard of their colour and a darker shade of it, with squares half a pixel wide, so they
+/// stand out from black and white.
+fn rasterise(image: &Image, scale: usize, palette: &Palette) -> Vec<u8> {
+    let checker = (scale / 2).max(1);
+    let mut bytes = Vec::with_capacity(image.width() * image.height() * scale * scale * 3);
+
+    for y in 0..image.height() * scale {
+        for x in 0..image.width() * scale {
+            let pixel = image[(y / scale, x / scale)];
+            let colour = palette.colour(pixel);
+            let Colour(rgb) = match pixel {
+                Pixel::Transparent if !(x / checker + y / checker).is_multiple_of(2) => {
+                    colour.darker()
+                }
+                _ => colour,
+            };
+            bytes.extend_from_slice(&rgb);
+        }
+    }
+
+    bytes
+}
+
+fn ppm(image: &Image, scale: usize, palette: &Palette) -> Vec<u8> {
+    let mut bytes = format!(
+        "P6\n{} {}\n255\n",
+        image.width() * scale,
+        image.height() * scale
+    )
+    .into_bytes();
+    bytes.extend(rasterise(image, scale, palette));
+    bytes
+}
+
+fn png(image: &Image, scale: usize, palette: &Palette) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    let mut encoder = png::Encoder::new(
+        &mut bytes,
+        (image.width() * scale) as u32,
+        (image.height() * scale) as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rasterise(image, scale, palette))?;
+    writer.finish()?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+fn example() -> Image {
+    Grid::from_rows(vec![vec![Pixel::Black, Pixel::White, Pixel::Transparent]]).unwrap()
+}
+
+#[test]
+fn ppm_test() {
+    let ppm = ppm(&example(), 2, &Palette::default());
+    let (header, pixels) = ppm.split_at(11);
+
+    assert_eq!(header, b"P6\n6 2\n255\n");
+    assert_eq!(
+        pixels.chunks(3).map(|rgb| rgb[0]).collect::<Vec<_>>(),
+        vec![0, 0, 255, 255, 204, 153, 0, 0, 255, 255, 153, 204]
+    );
+}
+
+#[test]
+fn png_test() {
+    let png = png(&example(), 3, &Palette::default()).unwrap();
+    let decoder = png::Decoder::new(png.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+
+    assert_eq!((info.width, info.height), (9, 3));
+    assert_eq!(pixels, rasterise(&example(), 3, &Palette::default()));
+}
+
+#[test]
+fn export_unknown_extension_test() {
+    assert_eq!(
+        error_message(export(
+            &example(),
+            Path::new("image.gif"),
+            1,
+            &Palette::default()
+        )),
+        "don't know how to export image.gif, expected a .png or .ppm file"
+    );
+}
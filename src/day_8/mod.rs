@@ -1,122 +1,215 @@
 use super::*;
-use std::collections::HashMap;
-use std::mem;
+use crate::geom::Grid;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 
-pub fn main(part: Part) -> Result<()> {
-    let input = read_file("input/day_8")?;
-    let input = input.trim_end_matches('\n');
+mod decoder;
+mod export;
+mod palette;
+mod render;
+mod stats;
+
+use palette::{Colour, Palette};
+use render::Format;
+
+pub use stats::{Histogram, LayerStats};
+
+pub(crate) fn main(part: Part, opt: &Opt) -> Result<()> {
+    let size = Size {
+        width: opt.width.unwrap_or(DEFAULT_SIZE.width),
+        height: opt.height.unwrap_or(DEFAULT_SIZE.height),
+    };
+
+    let format = match &opt.format {
+        Some(format) => Format::parse(format)?,
+        None => Format::Blocks,
+    };
+
+    let palette = Palette::parse(opt.colour.iter().map(String::as_str))?;
+
+    if opt.export.is_some() || opt.dump_layers.is_some() {
+        let input = read_input(opt, "input/day_8")?;
+        return export(input.trim_end(), size, &palette, opt);
+    }
+
+    let path = opt
+        .input
+        .as_deref()
+        .unwrap_or_else(|| Path::new("input/day_8"));
+    let input = BufReader::new(
+        File::open(path).map_err(|err| anyhow!("failed to read {}: {}", path.display(), err))?,
+    );
 
     match part {
-        Part::One => println!("{}", part_1(&input)?),
-        Part::Two => part_2(&input)?,
+        Part::One => println!("{}", checksum(input, size)?),
+        Part::Two => println!("{}", render_image(input, size, format, &palette)?),
     }
 
     Ok(())
 }
 
-fn part_1(input: &str) -> Result<usize> {
-    let layers = build_layers(input)?;
+/// Write the decoded image to `--export`, and each layer to `--dump-layers`.
+fn export(input: &str, size: Size, palette: &Palette, opt: &Opt) -> Result<()> {
+    let scale = opt.scale.unwrap_or(10);
+    if scale == 0 {
+        return Err(anyhow!("--scale must be at least 1"));
+    }
+
+    let layers = build_layers(input, size)?;
 
-    let layer_with_fewest_zeros = layers
-        .iter()
-        .min_by_key(|layer| number_of(Pixel::Black, layer))
-        .ok_or_else(|| anyhow!("no min layer"))?;
+    if let Some(dir) = &opt.dump_layers {
+        export::dump_layers(&layers, dir, scale, palette)?;
+    }
 
-    let answer = number_of(Pixel::White, layer_with_fewest_zeros)
-        * number_of(Pixel::Transparent, layer_with_fewest_zeros);
+    if let Some(path) = &opt.export {
+        export::export(&compose(&layers, size), path, scale, palette)?;
+    }
 
-    assert_eq!(1215, answer);
+    Ok(())
+}
 
-    Ok(answer)
+/// Find the layer with the fewest 0 digits of a 25x6 image, and multiply its number of 1 digits
+/// by its number of 2 digits.
+pub fn part_1(input: &str) -> Result<usize> {
+    checksum(input.as_bytes(), DEFAULT_SIZE)
 }
 
-fn part_2(input: &str) -> Result<()> {
-    let mut layers = build_layers(input)?;
-    let mut image = blank_image();
+/// The letters a 25x6 image spells out.
+pub fn part_2(input: &str) -> Result<String> {
+    render_image(
+        input.as_bytes(),
+        DEFAULT_SIZE,
+        Format::Text,
+        &Palette::default(),
+    )
+}
 
-    for row in 0..HEIGHT {
-        for col in 0..WIDTH {
-            let pixel = layers
-                .iter()
-                .map(|layer| &layer[row][col])
-                .find(|&&pixel| pixel != Pixel::Transparent)
-                .unwrap_or_else(|| &Pixel::Transparent);
+fn checksum(input: impl BufRead, size: Size) -> Result<usize> {
+    let mut layer_with_fewest_zeros: Option<Histogram> = None;
 
-            image[row][col] = *pixel;
+    decoder::decode(input, size, |histogram| {
+        if layer_with_fewest_zeros.is_none_or(|fewest| histogram.count(0) < fewest.count(0)) {
+            layer_with_fewest_zeros = Some(histogram);
         }
-    }
+    })?;
+
+    layer_with_fewest_zeros
+        .map(|layer| layer.product(&[1, 2]))
+        .ok_or_else(|| anyhow!("no min layer"))
+}
 
-    let mut html = "<table cellspacing=0>".to_string();
-    for row in image {
-        html.push_str("<tr>");
-        for pixel in row {
-            match pixel {
-                Pixel::White => html
-                    .push_str("<td style='height:3px; width:1px; background-color: white;'></td>"),
-                Pixel::Black | Pixel::Transparent => html
-                    .push_str("<td style='height:3px; width:1px; background-color: black;'></td>"),
-                Pixel::Black => html
-                    .push_str("<td style='height:3px; width:1px;'></td>"),
+fn render_image(
+    input: impl BufRead,
+    size: Size,
+    format: Format,
+    palette: &Palette,
+) -> Result<String> {
+    let image = decoder::decode(input, size, |_| {})?;
+
+    format.render(&to_image(&image), palette)
+}
+
+/// Stack the layers on top of each other, the first layer in front. Each pixel of the image is
+/// the first pixel in that position that isn't transparent.
+fn compose(layers: &[Layer], size: Size) -> Image {
+    let mut image = blank_image(size);
+
+    for row in 0..size.height {
+        for col in 0..size.width {
+            for layer in layers {
+                let pixel = Pixel::from_digit(layer[(row, col)]);
+
+                if pixel != Pixel::Transparent {
+                    image[(row, col)] = pixel;
+                    break;
+                }
             }
         }
-        html.push_str("</tr>");
     }
-    html.push_str("</table>");
-    println!("<body style='background-color:black'>{}</body>", html);
 
-    Ok(())
+    image
 }
 
-fn blank_image() -> Image {
-    let mut image = Vec::<Row>::with_capacity(HEIGHT);
-    for _ in 0..HEIGHT {
-        let row = (0..WIDTH).map(|_| Pixel::Transparent).collect::<Row>();
-        image.push(row);
-    }
-    image
+fn to_image(digits: &Grid<u8>) -> Image {
+    digits.map(|digit| Pixel::from_digit(*digit))
 }
 
-const WIDTH: usize = 25;
-const HEIGHT: usize = 6;
+fn blank_image(size: Size) -> Image {
+    Grid::new(size.width, size.height, Pixel::Transparent)
+}
 
-type Row = Vec<Pixel>;
-type Layer = Vec<Row>;
-type Image = Vec<Row>;
+/// The width and height of an image, and of each of its layers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
 
-fn build_layers(input: &str) -> Result<Vec<Layer>> {
-    let mut data = input
-        .chars()
-        .map(parse_char)
-        .collect::<Result<Vec<_>>>()?;
+impl Size {
+    /// The number of digits in a layer. Only call this once `check` has passed.
+    fn area(self) -> usize {
+        self.width * self.height
+    }
 
-    let mut layers = vec![];
+    fn check(self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(anyhow!(
+                "image size must be at least 1x1, got {}x{}",
+                self.width,
+                self.height
+            ));
+        }
 
-    while !data.is_empty() {
-        let mut layer = Vec::with_capacity(HEIGHT);
-        for _ in 0..HEIGHT {
-            let row = data.split_off_keep_tail(WIDTH);
-            layer.push(row);
+        if self.width.checked_mul(self.height).is_none() {
+            return Err(anyhow!(
+                "image size {}x{} is too large, a layer would have more than {} digits",
+                self.width,
+                self.height,
+                usize::MAX
+            ));
         }
-        layers.push(layer);
+
+        Ok(())
     }
 
-    Ok(layers)
+    fn partial_layer_error(self, digits: usize) -> Error {
+        anyhow!(
+            "{} digits don't fit into whole {}x{} layers of {} digits each",
+            digits,
+            self.width,
+            self.height,
+            self.area()
+        )
+    }
 }
 
-fn number_of(pixel: Pixel, layer: &Layer) -> usize {
-    layer
-        .iter()
-        .map(|row| row.iter().filter(|n| **n == pixel).count())
-        .sum::<usize>()
-}
+const DEFAULT_SIZE: Size = Size {
+    width: 25,
+    height: 6,
+};
+
+pub type Layer = Grid<u8>;
+type Image = Grid<Pixel>;
+
+/// Split the digits of an image into layers of `size`. Fails unless the digits fill a whole
+/// number of layers exactly.
+fn build_layers(input: &str, size: Size) -> Result<Vec<Layer>> {
+    size.check()?;
+
+    let data = input.chars().map(parse_char).collect::<Result<Vec<_>>>()?;
+
+    if data.is_empty() {
+        return Err(anyhow!("no image data"));
+    }
 
-#[ext]
-impl<T> Vec<T> {
-    fn split_off_keep_tail(&mut self, at: usize) -> Vec<T> {
-        let mut start = self.split_off(at);
-        mem::swap(&mut start, self);
-        start
+    if !data.len().is_multiple_of(size.area()) {
+        return Err(size.partial_layer_error(data.len()));
     }
+
+    data.chunks(size.area())
+        .map(|layer| Grid::from_vec(size.width, size.height, layer.to_vec()))
+        .collect()
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -124,14 +217,164 @@ enum Pixel {
     Black,       // 0
     White,       // 1
     Transparent, // 2
+    /// Any other digit, an opaque colour of its own.
+    Colour(u8),
+}
+
+impl Pixel {
+    fn from_digit(digit: u8) -> Self {
+        match digit {
+            0 => Pixel::Black,
+            1 => Pixel::White,
+            2 => Pixel::Transparent,
+            other => Pixel::Colour(other),
+        }
+    }
+
+    fn digit(self) -> u8 {
+        match self {
+            Pixel::Black => 0,
+            Pixel::White => 1,
+            Pixel::Transparent => 2,
+            Pixel::Colour(digit) => digit,
+        }
+    }
+
+    /// Whether the pixel shows up against the black background.
+    fn is_lit(self) -> bool {
+        matches!(self, Pixel::White | Pixel::Colour(_))
+    }
 }
 
 #[inline]
-fn parse_char(c: char) -> Result<Pixel> {
-    match c {
-        '0' => Ok(Pixel::Black),
-        '1' => Ok(Pixel::White),
-        '2' => Ok(Pixel::Transparent),
-        other => Err(anyhow!("Invalid digit: {}", other)),
+fn parse_char(c: char) -> Result<u8> {
+    c.to_digit(10)
+        .map(|digit| digit as u8)
+        .ok_or_else(|| anyhow!("Invalid digit: {}", c))
+}
+
+#[test]
+fn build_layers_test() {
+    let size = Size {
+        width: 3,
+        height: 2,
+    };
+    let layers = build_layers("123456789012", size).unwrap();
+
+    assert_eq!(
+        layers,
+        vec![
+            Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap(),
+            Grid::from_rows(vec![vec![7, 8, 9], vec![0, 1, 2]]).unwrap(),
+        ]
+    );
+    assert_eq!(Histogram::of(&layers[0]).count(0), 0);
+    assert_eq!(Histogram::of(&layers[0]).product(&[1, 2]), 1);
+}
+
+#[test]
+fn build_layers_errors_test() {
+    let size = |width, height| Size { width, height };
+
+    assert_eq!(
+        error_message(build_layers("12345678901", size(3, 2))),
+        "11 digits don't fit into whole 3x2 layers of 6 digits each"
+    );
+    assert_eq!(
+        error_message(build_layers("123456789012", size(5, 1))),
+        "12 digits don't fit into whole 5x1 layers of 5 digits each"
+    );
+    assert_eq!(error_message(build_layers("", size(3, 2))), "no image data");
+    assert_eq!(
+        error_message(build_layers("1234", size(0, 2))),
+        "image size must be at least 1x1, got 0x2"
+    );
+    assert_eq!(
+        error_message(build_layers("12a4", size(2, 2))),
+        "Invalid digit: a"
+    );
+}
+
+#[test]
+fn compose_test() {
+    let size = Size {
+        width: 2,
+        height: 2,
+    };
+    let layers = build_layers("0222112222120000", size).unwrap();
+
+    assert_eq!(layers.len(), 4);
+    assert_eq!(
+        compose(&layers, size),
+        Grid::from_rows(vec![
+            vec![Pixel::Black, Pixel::White],
+            vec![Pixel::White, Pixel::Black],
+        ])
+        .unwrap()
+    );
+
+    let layers = build_layers("2272812209021111", size).unwrap();
+    assert_eq!(
+        compose(&layers, size),
+        Grid::from_rows(vec![
+            vec![Pixel::Colour(8), Pixel::White],
+            vec![Pixel::Colour(7), Pixel::White],
+        ])
+        .unwrap()
+    );
+}
+
+#[test]
+fn part_1_test() {
+    let input = read_file("input/day_8").unwrap();
+
+    assert_eq!(part_1(&input).unwrap(), 1215);
+}
+
+#[test]
+fn part_2_test() {
+    let input = read_file("input/day_8").unwrap();
+
+    assert_eq!(part_2(&input).unwrap(), "LHCPH");
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SIZE: Size = Size {
+        width: 3,
+        height: 2,
+    };
+
+    fn layer() -> impl Strategy<Value = Layer> {
+        // Lean towards transparent pixels, so that the back layers still show through.
+        let digit = prop_oneof![3 => Just(2_u8), 1 => 0..10_u8];
+
+        prop::collection::vec(digit, SIZE.area())
+            .prop_map(|digits| Grid::from_vec(SIZE.width, SIZE.height, digits).unwrap())
+    }
+
+    /// The digits of a composed image, so that it can be stacked like any other layer.
+    fn flatten(image: &Image) -> Layer {
+        image.map(|pixel| pixel.digit())
+    }
+
+    proptest! {
+        #[test]
+        fn compose_is_associative(a in layer(), b in layer(), c in layer()) {
+            let all = compose(&[a.clone(), b.clone(), c.clone()], SIZE);
+            let front = flatten(&compose(&[a.clone(), b.clone()], SIZE));
+            let back = flatten(&compose(&[b, c.clone()], SIZE));
+
+            prop_assert_eq!(&compose(&[front, c], SIZE), &all);
+            prop_assert_eq!(&compose(&[a, back], SIZE), &all);
+        }
+
+        #[test]
+        fn single_layer_composes_to_itself(a in layer()) {
+            prop_assert_eq!(compose(std::slice::from_ref(&a), SIZE), to_image(&a));
+        }
     }
 }
@@ -0,0 +1,154 @@
+use super::*;
+use std::fmt;
+
+/// An RGB colour, written like CSS as `#rrggbb`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Colour(pub [u8; 3]);
+
+impl Colour {
+    /// Parse `#rgb` or `#rrggbb`.
+    pub fn parse(colour: &str) -> Result<Self> {
+        let invalid = || anyhow!("invalid colour '{}', expected #rgb or #rrggbb", colour);
+
+        let hex = colour.strip_prefix('#').ok_or_else(invalid)?;
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+
+        match hex.len() {
+            3 if hex.is_ascii() => {
+                let mut rgb = [0; 3];
+                for (idx, digit) in hex.char_indices() {
+                    rgb[idx] = channel(&digit.to_string())? * 0x11;
+                }
+                Ok(Colour(rgb))
+            }
+            6 if hex.is_ascii() => Ok(Colour([
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            ])),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The same colour at three quarters of the brightness.
+    pub fn darker(self) -> Self {
+        let Colour([r, g, b]) = self;
+        Colour([r / 4 * 3, g / 4 * 3, b / 4 * 3])
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Colour([r, g, b]) = self;
+        write!(f, "#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+/// The colour each kind of pixel is drawn with, indexed by digit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Palette([Colour; 10]);
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self([
+            Colour([0x00, 0x00, 0x00]),
+            Colour([0xff, 0xff, 0xff]),
+            Colour([0xcc, 0xcc, 0xcc]),
+            Colour([0x1f, 0x77, 0xb4]),
+            Colour([0xff, 0x7f, 0x0e]),
+            Colour([0x2c, 0xa0, 0x2c]),
+            Colour([0xd6, 0x27, 0x28]),
+            Colour([0x94, 0x67, 0xbd]),
+            Colour([0x8c, 0x56, 0x4b]),
+            Colour([0xe3, 0x77, 0xc2]),
+        ])
+    }
+}
+
+impl Palette {
+    /// The default palette with some colours replaced, given like `white=#ff0`,
+    /// `transparent=#00ff00` or `7=#808080`.
+    pub fn parse<'a>(overrides: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        overrides
+            .into_iter()
+            .try_fold(Self::default(), |mut palette, spec| {
+                let mut parts = spec.splitn(2, '=');
+                let (pixel, colour) = match (parts.next(), parts.next()) {
+                    (Some(pixel), Some(colour)) => (pixel, colour),
+                    _ => {
+                        return Err(anyhow!(
+                            "expected a colour like 'white=#ffffff', got '{}'",
+                            spec
+                        ))
+                    }
+                };
+
+                let pixel = match pixel {
+                    "black" => Pixel::Black,
+                    "white" => Pixel::White,
+                    "transparent" => Pixel::Transparent,
+                    digit if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() => {
+                        Pixel::from_digit(digit.as_bytes()[0] - b'0')
+                    }
+                    other => {
+                        return Err(anyhow!(
+                            "unknown pixel '{}', expected black, white, transparent or a digit",
+                            other
+                        ))
+                    }
+                };
+
+                palette.0[pixel.digit() as usize] = Colour::parse(colour)?;
+                Ok(palette)
+            })
+    }
+
+    pub fn colour(&self, pixel: Pixel) -> Colour {
+        self.0[pixel.digit() as usize]
+    }
+}
+
+#[test]
+fn colour_test() {
+    assert_eq!(Colour::parse("#fff").unwrap(), Colour([255, 255, 255]));
+    assert_eq!(
+        Colour::parse("#1a2B3c").unwrap(),
+        Colour([0x1a, 0x2b, 0x3c])
+    );
+    assert_eq!(Colour([0x1a, 0x2b, 0x3c]).to_string(), "#1a2b3c");
+    assert_eq!(
+        Colour([0xcc, 0xcc, 0xcc]).darker(),
+        Colour([0x99, 0x99, 0x99])
+    );
+
+    for invalid in &["fff", "#ffff", "#ggg", "#ü1", ""] {
+        assert!(Colour::parse(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn palette_test() {
+    let palette = Palette::parse(vec!["white=#ff0", "transparent=#00ff00", "7=#123"]).unwrap();
+
+    assert_eq!(palette.colour(Pixel::Black), Colour([0, 0, 0]));
+    assert_eq!(palette.colour(Pixel::White), Colour([255, 255, 0]));
+    assert_eq!(palette.colour(Pixel::Transparent), Colour([0, 255, 0]));
+    assert_eq!(palette.colour(Pixel::Colour(7)), Colour([0x11, 0x22, 0x33]));
+
+    assert_eq!(
+        error_message(Palette::parse(vec!["white"])),
+        "expected a colour like 'white=#ffffff', got 'white'"
+    );
+    assert_eq!(
+        error_message(Palette::parse(vec!["grey=#888"])),
+        "unknown pixel 'grey', expected black, white, transparent or a digit"
+    );
+    assert_eq!(
+        error_message(Palette::parse(vec!["x=#888"])),
+        "unknown pixel 'x', expected black, white, transparent or a digit"
+    );
+    assert_eq!(
+        error_message(Palette::parse(vec!["white=red"])),
+        "invalid colour 'red', expected #rgb or #rrggbb"
+    );
+}
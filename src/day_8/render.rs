@@ -0,0 +1,164 @@
+use super::*;
+use crate::ocr;
+
+/// How to print a decoded image.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// The letters the image spells out.
+    Text,
+    /// One `█` or space per pixel.
+    Blocks,
+    /// Two rows of pixels per line, using `▀`, `▄` and `█`.
+    HalfBlocks,
+    /// A table with one cell per pixel, to open in a browser.
+    Html,
+}
+
+impl Format {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "text" => Ok(Format::Text),
+            "blocks" => Ok(Format::Blocks),
+            "half-blocks" => Ok(Format::HalfBlocks),
+            "html" => Ok(Format::Html),
+            other => Err(anyhow!(
+                "unknown format '{}', expected text, blocks, half-blocks or html",
+                other
+            )),
+        }
+    }
+
+    pub fn render(self, image: &Image, palette: &Palette) -> Result<String> {
+        match self {
+            Format::Text => text(image),
+            Format::Blocks => Ok(blocks(image)),
+            Format::HalfBlocks => Ok(half_blocks(image)),
+            Format::Html => Ok(html(image, palette)),
+        }
+    }
+}
+
+/// Read the letters off the image, with white pixels lit.
+pub fn text(image: &Image) -> Result<String> {
+    ocr::recognise(&image.map(|pixel| *pixel == Pixel::White))
+        .map_err(|err| anyhow!("{}, try --format blocks to look at the image", err))
+}
+
+/// Draw white pixels as `█` and pixels of any other colour as their digit. Black and transparent
+/// pixels are left blank.
+pub fn blocks(image: &Image) -> String {
+    image.render(|pixel| match pixel {
+        Pixel::White => '█',
+        Pixel::Colour(digit) => (b'0' + digit) as char,
+        Pixel::Black | Pixel::Transparent => ' ',
+    })
+}
+
+/// Like `blocks`, but fit two rows of pixels into every line of text, so the image keeps roughly
+/// its proportions in a terminal.
+pub fn half_blocks(image: &Image) -> String {
+    let rows = image.rows().collect::<Vec<_>>();
+
+    rows.chunks(2)
+        .map(|pair| {
+            (0..image.width())
+                .map(|col| {
+                    let top = pair[0][col].is_lit();
+                    let bottom = pair.get(1).is_some_and(|row| row[col].is_lit());
+
+                    match (top, bottom) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A standalone HTML page with the image as a table, one cell per pixel. Each kind of pixel gets
+/// a CSS class, so the colours are only spelled out once.
+pub fn html(image: &Image, palette: &Palette) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n");
+    html.push_str("<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Day 8</title>\n<style>\n");
+    html.push_str(&format!(
+        "body {{ background-color: {}; }}\n",
+        palette.colour(Pixel::Black)
+    ));
+    html.push_str("table { border-collapse: collapse; }\n");
+    html.push_str("td { width: 1px; height: 3px; padding: 0; }\n");
+    for pixel in (0..10).map(Pixel::from_digit) {
+        html.push_str(&format!(
+            ".{} {{ background-color: {}; }}\n",
+            css_class(pixel),
+            palette.colour(pixel)
+        ));
+    }
+    html.push_str("</style>\n</head>\n<body>\n<table>\n");
+
+    for row in image.rows() {
+        html.push_str("<tr>");
+        for pixel in row {
+            html.push_str(&format!("<td class={}></td>", css_class(*pixel)));
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n</body>\n</html>");
+    html
+}
+
+fn css_class(pixel: Pixel) -> String {
+    match pixel {
+        Pixel::Black => "b".to_string(),
+        Pixel::White => "w".to_string(),
+        Pixel::Transparent => "t".to_string(),
+        Pixel::Colour(digit) => format!("c{}", digit),
+    }
+}
+
+#[cfg(test)]
+fn example() -> Image {
+    Grid::from_rows(vec![
+        vec![Pixel::White, Pixel::Black, Pixel::White],
+        vec![Pixel::White, Pixel::White, Pixel::Transparent],
+        vec![Pixel::Black, Pixel::White, Pixel::Colour(7)],
+    ])
+    .unwrap()
+}
+
+#[test]
+fn blocks_test() {
+    assert_eq!(blocks(&example()), "█ █\n██ \n █7");
+}
+
+#[test]
+fn half_blocks_test() {
+    assert_eq!(half_blocks(&example()), "█▄▀\n ▀▀");
+}
+
+#[test]
+fn format_parse_test() {
+    assert_eq!(Format::parse("html").unwrap(), Format::Html);
+    assert_eq!(
+        error_message(Format::parse("png")),
+        "unknown format 'png', expected text, blocks, half-blocks or html"
+    );
+}
+
+#[test]
+fn html_test() {
+    let palette = Palette::parse(vec!["transparent=#f00"]).unwrap();
+    let html = html(&example(), &palette);
+
+    assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n"));
+    assert!(html.ends_with("</table>\n</body>\n</html>"));
+    assert!(html.contains(".t { background-color: #ff0000; }\n"));
+    assert!(html.contains("<tr><td class=w></td><td class=b></td><td class=w></td></tr>\n"));
+    assert!(html.contains("<tr><td class=w></td><td class=w></td><td class=t></td></tr>\n"));
+}
@@ -0,0 +1,97 @@
+use super::*;
+use std::io::BufRead;
+
+/// How many times each digit appears in a layer.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Histogram([usize; 10]);
+
+impl Histogram {
+    pub fn of(layer: &Layer) -> Self {
+        let mut histogram = Self::default();
+        for digit in layer.iter() {
+            histogram.add(*digit);
+        }
+        histogram
+    }
+
+    /// Count one more `digit`. Anything above 9 isn't a digit and is ignored.
+    pub fn add(&mut self, digit: u8) {
+        if let Some(count) = self.0.get_mut(digit as usize) {
+            *count += 1;
+        }
+    }
+
+    /// How many times `digit` appears, which is 0 for anything above 9.
+    pub fn count(&self, digit: u8) -> usize {
+        self.0.get(digit as usize).copied().unwrap_or(0)
+    }
+
+    /// The counts of `digits` multiplied together, so 1 if `digits` is empty.
+    pub fn product(&self, digits: &[u8]) -> usize {
+        digits.iter().map(|digit| self.count(*digit)).product()
+    }
+}
+
+/// The histogram of every layer of an image, front to back.
+#[derive(Debug)]
+pub struct LayerStats {
+    histograms: Vec<Histogram>,
+}
+
+impl LayerStats {
+    pub fn decode(input: impl BufRead, size: Size) -> Result<Self> {
+        let mut histograms = vec![];
+        decoder::decode(input, size, |histogram| histograms.push(histogram))?;
+        Ok(Self { histograms })
+    }
+
+    pub fn histograms(&self) -> &[Histogram] {
+        &self.histograms
+    }
+
+    /// The layer with the fewest `digit`s. The front-most one wins a tie.
+    pub fn fewest(&self, digit: u8) -> Option<&Histogram> {
+        self.histograms
+            .iter()
+            .min_by_key(|histogram| histogram.count(digit))
+    }
+
+    /// The layer with the most `digit`s. The front-most one wins a tie.
+    pub fn most(&self, digit: u8) -> Option<&Histogram> {
+        self.histograms
+            .iter()
+            .rev()
+            .max_by_key(|histogram| histogram.count(digit))
+    }
+}
+
+#[test]
+fn histogram_test() {
+    let layer = Grid::from_rows(vec![vec![1, 2, 2], vec![0, 9, 2], vec![12, 10, 255]]).unwrap();
+    let histogram = Histogram::of(&layer);
+
+    assert_eq!(histogram.count(2), 3);
+    assert_eq!(histogram.count(5), 0);
+    assert_eq!(histogram.count(10), 0);
+    assert_eq!(histogram.count(12), 0);
+    assert_eq!(histogram.product(&[1, 2]), 3);
+    assert_eq!(histogram.product(&[2, 2, 9]), 9);
+    assert_eq!(histogram.product(&[]), 1);
+}
+
+#[test]
+fn layer_stats_test() {
+    let size = Size {
+        width: 2,
+        height: 2,
+    };
+    let stats = LayerStats::decode("001201120222".as_bytes(), size).unwrap();
+
+    assert_eq!(stats.histograms().len(), 3);
+    assert_eq!(stats.fewest(0), Some(&stats.histograms()[1]));
+    assert_eq!(stats.most(0), Some(&stats.histograms()[0]));
+    assert_eq!(stats.most(2), Some(&stats.histograms()[2]));
+    assert_eq!(stats.fewest(1), Some(&stats.histograms()[2]));
+    assert_eq!(stats.most(9), Some(&stats.histograms()[0]));
+    assert_eq!(stats.fewest(10), Some(&stats.histograms()[0]));
+}
@@ -0,0 +1,526 @@
+#[cfg(test)]
+use super::error_message;
+use super::{Error, Result};
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+/// A point on an infinite 2D plane where `y` grows upwards.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn zero() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    /// The point `n` steps away in `direction`.
+    pub fn step(self, direction: Direction, n: i32) -> Self {
+        let (dx, dy) = direction.offset();
+        Self {
+            x: self.x + dx * n,
+            y: self.y + dy * n,
+        }
+    }
+
+    /// The point `n` steps away in `direction`, or `None` if it is out of range of an `i32`.
+    pub fn checked_step(self, direction: Direction, n: i32) -> Option<Self> {
+        let (dx, dy) = direction.offset();
+        Some(Self {
+            x: self.x.checked_add(dx.checked_mul(n)?)?,
+            y: self.y.checked_add(dy.checked_mul(n)?)?,
+        })
+    }
+
+    /// The four points directly above, below, left, and right of this one.
+    pub fn neighbours(self) -> [Self; 4] {
+        let mut neighbours = [self; 4];
+        for (neighbour, direction) in neighbours.iter_mut().zip(&Direction::ALL) {
+            *neighbour = self.step(*direction, 1);
+        }
+        neighbours
+    }
+}
+
+pub fn manhattan_distance(p: Point, q: Point) -> i32 {
+    (p.x - q.x).abs() + (p.y - q.y).abs()
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// How much `x` and `y` change when taking one step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    /// Parse the `U`, `D`, `L`, and `R` letters used by puzzle inputs.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// Parse lines of comma separated steps such as `R8,U5,L5,D3`, one list of steps per line.
+///
+/// Whitespace around steps and blank lines are ignored. Distances must be positive. Errors say
+/// which line and which step on that line failed, both counting from 1.
+pub fn parse_steps(input: &str) -> Result<Vec<Vec<(Direction, i32)>>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_idx, line)| {
+            line.split(',')
+                .enumerate()
+                .map(|(token_idx, token)| {
+                    parse_step(token.trim()).map_err(|msg| {
+                        Error::msg(format!(
+                            "line {}, token {}: {}",
+                            line_idx + 1,
+                            token_idx + 1,
+                            msg
+                        ))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn parse_step(token: &str) -> Result<(Direction, i32), String> {
+    let mut chars = token.chars();
+
+    let direction = match chars.next() {
+        None => return Err("empty step".to_string()),
+        Some(c) => Direction::from_char(c).ok_or_else(|| format!("invalid direction '{}'", c))?,
+    };
+
+    let distance = chars.as_str().trim();
+    if distance.is_empty() {
+        return Err(format!("missing distance in '{}'", token));
+    }
+
+    match distance.parse::<i32>() {
+        Ok(n) if n > 0 => Ok((direction, n)),
+        Ok(n) => Err(format!("distance must be positive, got {}", n)),
+        Err(_) => Err(format!("invalid distance '{}'", distance)),
+    }
+}
+
+/// The smallest rectangle containing a set of points. Both corners are inclusive.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, point| {
+            let bounds = bounds.unwrap_or(Bounds {
+                min: point,
+                max: point,
+            });
+
+            Some(Bounds {
+                min: Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y)),
+                max: Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y)),
+            })
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y) as usize + 1
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+/// A dense, fixed size grid stored row by row.
+///
+/// Cells are addressed as `(row, col)` with row 0 at the top.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Build a grid from cells stored row by row. Fails if the cells don't exactly fill
+    /// `width * height`.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        if cells.len() != width * height {
+            return Err(Error::msg(format!(
+                "{} cells don't fit a {}x{} grid",
+                cells.len(),
+                width,
+                height
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Build a grid from its rows. Fails if the rows aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            return Err(Error::msg(format!(
+                "row {} has {} cells, expected {}",
+                row,
+                rows[row].len(),
+                width
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            self.cells.get(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.height && col < self.width {
+            self.cells.get_mut(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    /// All cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// Draw the grid with one character per cell and one line per row.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside a {}x{} grid",
+                row, col, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(row, col)
+            .unwrap_or_else(|| panic!("({}, {}) is outside a {}x{} grid", row, col, width, height))
+    }
+}
+
+/// A grid without fixed bounds that only stores the points that have been set.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.cells.keys().copied())
+    }
+
+    /// Draw the points within `bounds()` with one character per point, using `empty` for
+    /// points that haven't been set. The highest `y` is drawn on the first line.
+    pub fn render(&self, empty: char, f: impl Fn(&T) -> char) -> String {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+
+        (bounds.min.y..=bounds.max.y)
+            .rev()
+            .map(|y| {
+                (bounds.min.x..=bounds.max.x)
+                    .map(|x| self.get(Point::new(x, y)).map(&f).unwrap_or(empty))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[test]
+fn manhattan_distance_test() {
+    let p = Point::zero();
+
+    let q = Point::new(3, 3);
+    assert_eq!(manhattan_distance(p, q), 6);
+
+    let q = Point::new(6, 5);
+    assert_eq!(manhattan_distance(p, q), 11);
+}
+
+#[test]
+fn step_test() {
+    let p = Point::new(1, 1);
+
+    assert_eq!(p.step(Direction::Up, 3), Point::new(1, 4));
+    assert_eq!(p.step(Direction::Left, 2), Point::new(-1, 1));
+    assert_eq!(p.checked_step(Direction::Left, 2), Some(Point::new(-1, 1)));
+    assert_eq!(p.checked_step(Direction::Right, i32::MAX), None);
+    assert_eq!(
+        p.checked_step(Direction::Down, i32::MAX),
+        Some(Point::new(1, -2147483646))
+    );
+    assert_eq!(
+        p.neighbours(),
+        [
+            Point::new(1, 2),
+            Point::new(1, 0),
+            Point::new(0, 1),
+            Point::new(2, 1)
+        ]
+    );
+}
+
+#[test]
+fn parse_steps_test() {
+    use Direction::*;
+
+    assert_eq!(
+        parse_steps("R8, U5,L5\r\n\n D3 ,L12\r\n").unwrap(),
+        vec![
+            vec![(Right, 8), (Up, 5), (Left, 5)],
+            vec![(Down, 3), (Left, 12)]
+        ]
+    );
+}
+
+#[test]
+fn parse_steps_error_test() {
+    assert_eq!(
+        error_message(parse_steps("R1\nU2,X3")),
+        "line 2, token 2: invalid direction 'X'"
+    );
+    assert_eq!(
+        error_message(parse_steps("R1,,U2")),
+        "line 1, token 2: empty step"
+    );
+    assert_eq!(
+        error_message(parse_steps("R1,")),
+        "line 1, token 2: empty step"
+    );
+    assert_eq!(
+        error_message(parse_steps("R1,U")),
+        "line 1, token 2: missing distance in 'U'"
+    );
+    assert_eq!(
+        error_message(parse_steps("R1,U1x")),
+        "line 1, token 2: invalid distance '1x'"
+    );
+    assert_eq!(
+        error_message(parse_steps("R0")),
+        "line 1, token 1: distance must be positive, got 0"
+    );
+    assert_eq!(
+        error_message(parse_steps("L-3")),
+        "line 1, token 1: distance must be positive, got -3"
+    );
+}
+
+#[test]
+fn bounds_test() {
+    let bounds =
+        Bounds::from_points(vec![Point::new(2, -1), Point::new(-3, 4), Point::new(0, 0)]).unwrap();
+
+    assert_eq!(bounds.min, Point::new(-3, -1));
+    assert_eq!(bounds.max, Point::new(2, 4));
+    assert_eq!(bounds.width(), 6);
+    assert_eq!(bounds.height(), 6);
+    assert!(bounds.contains(Point::new(2, 4)));
+    assert!(!bounds.contains(Point::new(3, 4)));
+    assert!(Bounds::from_points(vec![]).is_none());
+}
+
+#[test]
+fn grid_test() {
+    let mut grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+
+    assert_eq!(grid.width(), 3);
+    assert_eq!(grid.height(), 2);
+    assert_eq!(grid[(1, 0)], 4);
+    assert_eq!(grid.get(0, 3), None);
+
+    grid[(0, 2)] = 9;
+    assert_eq!(grid.render(|n| (b'0' + *n as u8) as char), "129\n456");
+    assert_eq!(
+        grid.map(|n| n % 2 == 0)
+            .render(|even| if *even { 'e' } else { 'o' }),
+        "oeo\neoe"
+    );
+
+    assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    assert!(Grid::from_vec(2, 2, vec![1, 2, 3]).is_err());
+}
+
+#[test]
+fn sparse_grid_test() {
+    let mut grid = SparseGrid::new();
+    grid.insert(Point::new(0, 0), 'a');
+    grid.insert(Point::new(2, 1), 'b');
+
+    assert_eq!(grid.render('.', |c| *c), "..b\na..");
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Point> {
+        (-100_000..100_000, -100_000..100_000).prop_map(|(x, y)| Point::new(x, y))
+    }
+
+    proptest! {
+        #[test]
+        fn manhattan_distance_is_symmetric(p in point(), q in point()) {
+            prop_assert_eq!(manhattan_distance(p, q), manhattan_distance(q, p));
+            prop_assert_eq!(manhattan_distance(p, p), 0);
+        }
+
+        #[test]
+        fn manhattan_distance_triangle_inequality(p in point(), q in point(), r in point()) {
+            let via_q = manhattan_distance(p, q) + manhattan_distance(q, r);
+            prop_assert!(manhattan_distance(p, r) <= via_q);
+        }
+    }
+}
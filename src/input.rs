@@ -0,0 +1,91 @@
+//! Parsing puzzle inputs shared between days.
+
+#[cfg(test)]
+use crate::error_message;
+use crate::{Error, Result};
+use std::str::FromStr;
+
+/// Parse a comma separated list of integers, like an Intcode program.
+///
+/// Whitespace around each integer is ignored, including line breaks and `\r\n`, and so is a
+/// trailing comma. Fails on an empty input, or on a token that isn't an integer of type `T`.
+/// Errors say which token it was, counting from 1, and at which byte it starts.
+pub fn parse_ints<T: FromStr>(input: &str) -> Result<Vec<T>> {
+    if input.trim().is_empty() {
+        return Err(Error::msg(
+            "expected comma separated integers, got an empty input",
+        ));
+    }
+
+    let mut ints = Vec::new();
+    let mut offset = 0;
+    let mut tokens = input.split(',').enumerate().peekable();
+
+    while let Some((idx, raw)) = tokens.next() {
+        let token = raw.trim();
+        let start = offset + (raw.len() - raw.trim_start().len());
+        offset += raw.len() + 1;
+
+        let is_last = tokens.peek().is_none();
+        if token.is_empty() && is_last && idx > 0 {
+            break;
+        }
+
+        let int = token
+            .parse::<T>()
+            .map_err(|_| Error::msg(format!("token {} at byte {}: {:?}", idx + 1, start, token)))?;
+        ints.push(int);
+    }
+
+    if ints.is_empty() {
+        return Err(Error::msg(
+            "expected comma separated integers, got an empty input",
+        ));
+    }
+
+    Ok(ints)
+}
+
+#[test]
+fn parse_ints_test() {
+    assert_eq!(parse_ints::<i32>("1,-2,3").unwrap(), vec![1, -2, 3]);
+    assert_eq!(parse_ints::<i32>("1, 2,\n3\n").unwrap(), vec![1, 2, 3]);
+    assert_eq!(parse_ints::<i32>("1,2,3,\r\n").unwrap(), vec![1, 2, 3]);
+    assert_eq!(parse_ints::<i64>("4294967296").unwrap(), vec![1 << 32]);
+    assert_eq!(
+        parse_ints::<i128>("-170141183460469231731687303715884105728").unwrap(),
+        vec![i128::MIN]
+    );
+}
+
+#[test]
+fn parse_ints_errors_test() {
+    assert_eq!(
+        error_message(parse_ints::<i32>("1,2, 1x,4")),
+        "token 3 at byte 5: \"1x\""
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>("1,,2")),
+        "token 2 at byte 2: \"\""
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>("1,2,,")),
+        "token 3 at byte 4: \"\""
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>("4294967296")),
+        "token 1 at byte 0: \"4294967296\""
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>("")),
+        "expected comma separated integers, got an empty input"
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>(" \n")),
+        "expected comma separated integers, got an empty input"
+    );
+    assert_eq!(
+        error_message(parse_ints::<i32>(",")),
+        "token 1 at byte 0: \"\""
+    );
+}
@@ -0,0 +1,510 @@
+//! The Intcode computer.
+
+#[cfg(test)]
+use crate::error_message;
+use crate::{Error, Result};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// An Intcode computer that supports addition, multiplication, input, output and halting, with
+/// parameters in position or immediate mode.
+#[derive(Debug)]
+pub struct IntMachine {
+    mem: Vec<i32>,
+    ip: usize,
+}
+
+impl IntMachine {
+    /// A machine with a copy of `mem` as its memory, about to run the first instruction.
+    pub fn new(mem: &[i32]) -> Self {
+        Self {
+            mem: mem.to_vec(),
+            ip: 0,
+        }
+    }
+
+    pub fn mem(&self) -> &[i32] {
+        &self.mem
+    }
+
+    pub fn mem_mut(&mut self) -> &mut [i32] {
+        &mut self.mem
+    }
+
+    /// Run instructions until the program halts. The program gets no input, and anything it
+    /// outputs is thrown away.
+    pub fn run_to_completion(&mut self) -> Result<()> {
+        self.run(io::empty(), io::sink())
+    }
+
+    /// Run instructions until the program halts, reading one integer per line from `input`
+    /// whenever it asks for one, and writing every output on its own line.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        loop {
+            match self.tick(&mut input, &mut output)? {
+                TickOutput::Done => break,
+                TickOutput::NotDone => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a single instruction.
+    pub fn tick(&mut self, input: impl BufRead, mut output: impl Write) -> Result<TickOutput> {
+        let instruction = decode(&self.mem, self.ip)?;
+
+        match instruction {
+            Instruction::Add { a, b, dest } => {
+                self.run_arithmetic_op(a, b, dest, i32::checked_add)?
+            }
+            Instruction::Mul { a, b, dest } => {
+                self.run_arithmetic_op(a, b, dest, i32::checked_mul)?
+            }
+            Instruction::Input { dest } => {
+                let value = read_int(input)?;
+                self.write(dest, value)?;
+            }
+            Instruction::Output { value } => writeln!(output, "{}", self.read(value)?)?,
+            Instruction::Halt => return Ok(TickOutput::Done),
+        }
+
+        self.ip += instruction.size();
+        Ok(TickOutput::NotDone)
+    }
+
+    /// Write `f` of the values of `a` and `b` to `dest`. Fails if `f` overflows and returns
+    /// `None`.
+    fn run_arithmetic_op(
+        &mut self,
+        a: Param,
+        b: Param,
+        dest: usize,
+        f: impl Fn(i32, i32) -> Option<i32>,
+    ) -> Result<()> {
+        let (a, b) = (self.read(a)?, self.read(b)?);
+        let value = f(a, b).ok_or_else(|| {
+            Error::msg(format!(
+                "the instruction at {} overflows with {} and {}",
+                self.ip, a, b
+            ))
+        })?;
+
+        self.write(dest, value)
+    }
+
+    fn read(&self, param: Param) -> Result<i32> {
+        match param {
+            Param::Position(address) => self
+                .mem
+                .get(address)
+                .copied()
+                .ok_or_else(|| Error::msg(format!("address {} is outside of memory", address))),
+            Param::Immediate(value) => Ok(value),
+        }
+    }
+
+    fn write(&mut self, address: usize, value: i32) -> Result<()> {
+        let cell = self
+            .mem
+            .get_mut(address)
+            .ok_or_else(|| Error::msg(format!("address {} is outside of memory", address)))?;
+        *cell = value;
+
+        Ok(())
+    }
+}
+
+/// Read the next line of `input` as an integer.
+fn read_int(mut input: impl BufRead) -> Result<i32> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(Error::msg(
+            "the program wants input, but there is none left",
+        ));
+    }
+
+    line.trim()
+        .parse()
+        .map_err(|_| Error::msg(format!("invalid input '{}'", line.trim())))
+}
+
+/// Where an instruction takes a value from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Param {
+    /// The value at this address.
+    Position(usize),
+    /// The value itself.
+    Immediate(i32),
+}
+
+impl Param {
+    /// The digit of the op code that selects this mode, and the integer stored for the parameter.
+    fn encode(self) -> (i32, i32) {
+        match self {
+            Param::Position(address) => (0, address as i32),
+            Param::Immediate(value) => (1, value),
+        }
+    }
+}
+
+/// A single instruction, with its parameters. Instructions always write to an address.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Instruction {
+    Add {
+        a: Param,
+        b: Param,
+        dest: usize,
+    },
+    Mul {
+        a: Param,
+        b: Param,
+        dest: usize,
+    },
+    /// Read an integer and store it at `dest`.
+    Input {
+        dest: usize,
+    },
+    Output {
+        value: Param,
+    },
+    Halt,
+}
+
+impl Instruction {
+    /// The number of integers the instruction takes up in memory.
+    pub fn size(self) -> usize {
+        match self {
+            Instruction::Add { .. } | Instruction::Mul { .. } => 4,
+            Instruction::Input { .. } | Instruction::Output { .. } => 2,
+            Instruction::Halt => 1,
+        }
+    }
+}
+
+/// Turn instructions into the integers of a program.
+pub fn assemble(instructions: &[Instruction]) -> Vec<i32> {
+    let mut mem = Vec::new();
+
+    let arithmetic = |op_code: i32, a: Param, b: Param, dest: usize| {
+        let ((a_mode, a), (b_mode, b)) = (a.encode(), b.encode());
+        [op_code + 100 * a_mode + 1000 * b_mode, a, b, dest as i32]
+    };
+
+    for instruction in instructions {
+        match *instruction {
+            Instruction::Add { a, b, dest } => mem.extend(&arithmetic(1, a, b, dest)),
+            Instruction::Mul { a, b, dest } => mem.extend(&arithmetic(2, a, b, dest)),
+            Instruction::Input { dest } => mem.extend(&[3, dest as i32]),
+            Instruction::Output { value } => {
+                let (mode, value) = value.encode();
+                mem.extend(&[4 + 100 * mode, value]);
+            }
+            Instruction::Halt => mem.push(99),
+        }
+    }
+
+    mem
+}
+
+/// Read all of `mem` as instructions, one after another. Fails if something isn't a valid
+/// instruction, including when the last one is cut off.
+pub fn disassemble(mem: &[i32]) -> Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut ip = 0;
+
+    while ip < mem.len() {
+        let instruction = decode(mem, ip)?;
+        instructions.push(instruction);
+        ip += instruction.size();
+    }
+
+    Ok(instructions)
+}
+
+/// The instruction starting at `ip`.
+fn decode(mem: &[i32], ip: usize) -> Result<Instruction> {
+    let code = *mem
+        .get(ip)
+        .ok_or_else(|| Error::msg(format!("instruction pointer {} is outside of memory", ip)))?;
+
+    let raw = |n: usize| {
+        mem.get(ip + n)
+            .copied()
+            .ok_or_else(|| Error::msg(format!("instruction at {} is cut off", ip)))
+    };
+    let address = |n: usize| {
+        let raw = raw(n)?;
+        usize::try_from(raw)
+            .map_err(|_| Error::msg(format!("negative address {} at {}", raw, ip + n)))
+    };
+    // The mode of the first parameter is the hundreds digit, the second the thousands, and so on.
+    let mode = |n: usize| code / 10_i32.pow(n as u32 + 1) % 10;
+    let param = |n: usize| match mode(n) {
+        0 => address(n).map(Param::Position),
+        1 => raw(n).map(Param::Immediate),
+        other => Err(Error::msg(format!(
+            "invalid mode {} for parameter {} of the instruction at {}",
+            other, n, ip
+        ))),
+    };
+    let dest = |n: usize| match mode(n) {
+        0 => address(n),
+        other => Err(Error::msg(format!(
+            "invalid mode {} for parameter {} of the instruction at {}, which it writes to",
+            other, n, ip
+        ))),
+    };
+
+    match code % 100 {
+        1 => Ok(Instruction::Add {
+            a: param(1)?,
+            b: param(2)?,
+            dest: dest(3)?,
+        }),
+        2 => Ok(Instruction::Mul {
+            a: param(1)?,
+            b: param(2)?,
+            dest: dest(3)?,
+        }),
+        3 => Ok(Instruction::Input { dest: dest(1)? }),
+        4 => Ok(Instruction::Output { value: param(1)? }),
+        99 => Ok(Instruction::Halt),
+        _ => Err(Error::msg(format!("Invalid op code {}", code))),
+    }
+}
+
+/// Whether the program halted after an instruction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TickOutput {
+    Done,
+    NotDone,
+}
+
+impl fmt::Display for IntMachine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mem = self
+            .mem
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(f, "{}", mem)
+    }
+}
+
+#[test]
+fn test() {
+    let ops = "1,9,10,3,2,3,11,0,99,30,40,50";
+    let mem = crate::input::parse_ints(ops).unwrap();
+    let mut machine = IntMachine::new(&mem);
+    machine.run_to_completion().unwrap();
+
+    assert_eq!(machine.to_string(), "3500,9,10,70,2,3,11,0,99,30,40,50");
+}
+
+#[test]
+fn overflow_test() {
+    assert_eq!(
+        error_message(IntMachine::new(&[1, 0, 5, 0, 99, i32::MAX]).run_to_completion()),
+        "the instruction at 0 overflows with 1 and 2147483647"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[1, 9, 9, 9, 2, 9, 9, 0, 99, 1 << 16]).run_to_completion()),
+        "the instruction at 4 overflows with 131072 and 131072"
+    );
+}
+
+#[test]
+fn parameter_modes_test() {
+    let mut machine = IntMachine::new(&[1002, 4, 3, 4, 33]);
+    machine.run_to_completion().unwrap();
+    assert_eq!(machine.mem()[4], 99);
+
+    let mut machine = IntMachine::new(&[1101, 100, -1, 4, 0]);
+    machine.run_to_completion().unwrap();
+    assert_eq!(machine.mem()[4], 99);
+}
+
+#[test]
+fn input_output_test() {
+    let mut output = vec![];
+    IntMachine::new(&[3, 0, 4, 0, 99])
+        .run("42\n".as_bytes(), &mut output)
+        .unwrap();
+    assert_eq!(output, b"42\n");
+
+    let mut output = vec![];
+    IntMachine::new(&[104, -7, 99])
+        .run("".as_bytes(), &mut output)
+        .unwrap();
+    assert_eq!(output, b"-7\n");
+
+    assert_eq!(
+        error_message(IntMachine::new(&[3, 0, 99]).run_to_completion()),
+        "the program wants input, but there is none left"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[3, 0, 99]).run("x\n".as_bytes(), vec![])),
+        "invalid input 'x'"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[3, 5, 99]).run("1\n".as_bytes(), vec![])),
+        "address 5 is outside of memory"
+    );
+}
+
+#[test]
+fn memory_errors_test() {
+    assert_eq!(
+        error_message(IntMachine::new(&[1, 0, 0, 0]).run_to_completion()),
+        "instruction pointer 4 is outside of memory"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[1, 0, 7, 0, 99]).run_to_completion()),
+        "address 7 is outside of memory"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[1101, 1, 1, 7, 99]).run_to_completion()),
+        "address 7 is outside of memory"
+    );
+    assert_eq!(
+        error_message(IntMachine::new(&[4, 5, 99]).run_to_completion()),
+        "address 5 is outside of memory"
+    );
+}
+
+#[test]
+fn disassemble_test() {
+    let mem = crate::input::parse_ints::<i32>("1,9,10,3,1002,3,11,0,3,5,104,-2,99").unwrap();
+
+    assert_eq!(
+        disassemble(&mem).unwrap(),
+        vec![
+            Instruction::Add {
+                a: Param::Position(9),
+                b: Param::Position(10),
+                dest: 3
+            },
+            Instruction::Mul {
+                a: Param::Position(3),
+                b: Param::Immediate(11),
+                dest: 0
+            },
+            Instruction::Input { dest: 5 },
+            Instruction::Output {
+                value: Param::Immediate(-2)
+            },
+            Instruction::Halt,
+        ]
+    );
+
+    assert_eq!(
+        error_message(disassemble(&[1, 2, 3])),
+        "instruction at 0 is cut off"
+    );
+    assert_eq!(
+        error_message(disassemble(&[99, 2, 0, -1, 0])),
+        "negative address -1 at 3"
+    );
+    assert_eq!(error_message(disassemble(&[99, 7])), "Invalid op code 7");
+    assert_eq!(
+        error_message(disassemble(&[201, 0, 0, 0])),
+        "invalid mode 2 for parameter 1 of the instruction at 0"
+    );
+    assert_eq!(
+        error_message(disassemble(&[99, 10001, 0, 0, 0])),
+        "invalid mode 1 for parameter 3 of the instruction at 1, which it writes to"
+    );
+    assert_eq!(
+        error_message(disassemble(&[103, 0])),
+        "invalid mode 1 for parameter 1 of the instruction at 0, which it writes to"
+    );
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn param() -> impl Strategy<Value = Param> {
+        prop_oneof![
+            (0..1000_usize).prop_map(Param::Position),
+            any::<i32>().prop_map(Param::Immediate),
+        ]
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        let address = 0..1000_usize;
+
+        prop_oneof![
+            (param(), param(), address.clone()).prop_map(|(a, b, dest)| Instruction::Add {
+                a,
+                b,
+                dest
+            }),
+            (param(), param(), address.clone()).prop_map(|(a, b, dest)| Instruction::Mul {
+                a,
+                b,
+                dest
+            }),
+            address.prop_map(|dest| Instruction::Input { dest }),
+            param().prop_map(|value| Instruction::Output { value }),
+            Just(Instruction::Halt),
+        ]
+    }
+
+    /// Any `i32`, but often a small one so that multiplying two of them doesn't always
+    /// overflow.
+    fn operand() -> impl Strategy<Value = i32> {
+        prop_oneof![any::<i32>(), -100_000..100_000]
+    }
+
+    /// Run `instruction` on the operands `x` and `y`, and return what it wrote.
+    fn run(instruction: fn(Param, Param, usize) -> Instruction, x: i32, y: i32) -> Result<i32> {
+        let (a, b) = (Param::Position(5), Param::Position(6));
+        let mut mem = assemble(&[instruction(a, b, 7), Instruction::Halt]);
+        mem.extend(&[x, y, 0]);
+
+        let mut machine = IntMachine::new(&mem);
+        machine.run_to_completion()?;
+        Ok(machine.mem()[7])
+    }
+
+    proptest! {
+        #[test]
+        fn assemble_then_disassemble(instructions in prop::collection::vec(instruction(), 0..50)) {
+            prop_assert_eq!(disassemble(&assemble(&instructions)).unwrap(), instructions);
+        }
+
+        #[test]
+        fn add_matches_rust(x in operand(), y in operand()) {
+            let add = |a, b, dest| Instruction::Add { a, b, dest };
+            prop_assert_eq!(run(add, x, y).ok(), x.checked_add(y));
+        }
+
+        #[test]
+        fn mul_matches_rust(x in operand(), y in operand()) {
+            let mul = |a, b, dest| Instruction::Mul { a, b, dest };
+            prop_assert_eq!(run(mul, x, y).ok(), x.checked_mul(y));
+        }
+
+        #[test]
+        fn immediate_operands_match_rust(x in operand(), y in operand()) {
+            let (a, b) = (Param::Immediate(x), Param::Immediate(y));
+            let mut machine = IntMachine::new(&assemble(&[
+                Instruction::Add { a, b, dest: 0 },
+                Instruction::Output { value: Param::Position(0) },
+                Instruction::Halt,
+            ]));
+
+            let mut output = vec![];
+            let added = machine.run("".as_bytes(), &mut output).map(|()| machine.mem()[0]);
+            prop_assert_eq!(added.as_ref().ok().copied(), x.checked_add(y));
+            if let Ok(added) = added {
+                prop_assert_eq!(output, format!("{}\n", added).into_bytes());
+            }
+        }
+    }
+}
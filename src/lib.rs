@@ -0,0 +1,183 @@
+//! Solutions to Advent of Code 2019, and the pieces they're built from.
+//!
+//! The shared subsystems are [`geom`] for points and grids, [`input`] for parsing puzzle inputs,
+//! [`intcode`] for the Intcode computer and [`ocr`] for reading block letters. The orbit map
+//! lives in [`day_6`].
+//!
+//! Every `day_N` module has `part_1` and `part_2` functions that take the puzzle input and
+//! return the answer, for the parts that are solved. The `aoc` binary is a command line
+//! interface on top of [`run`].
+
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+pub use anyhow::{anyhow, Error, Result};
+
+/// Command line options of the `aoc` binary.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aoc")]
+pub struct Opt {
+    #[structopt(name = "DAY")]
+    day: usize,
+
+    #[structopt(name = "PART")]
+    part: Option<usize>,
+
+    /// Read the puzzle input from this file instead of `input/day_N`
+    #[structopt(long, parse(from_os_str))]
+    input: Option<PathBuf>,
+
+    /// Draw the puzzle to a file instead of solving. Day 3 draws the wires as SVG, day 6 the
+    /// orbit map as DOT, or Mermaid if the file ends in `.mmd`
+    #[structopt(long, parse(from_os_str))]
+    render: Option<PathBuf>,
+
+    /// Value to put at address 1 before running part 1, instead of 12 (day 2)
+    #[structopt(long)]
+    noun: Option<i32>,
+
+    /// Value to put at address 2 before running part 1, instead of 2 (day 2)
+    #[structopt(long)]
+    verb: Option<i32>,
+
+    /// Password rule to use instead of the part's rules, e.g. `--rule "run exactly 2"` (day 4)
+    #[structopt(long, number_of_values = 1)]
+    rule: Vec<String>,
+
+    /// File with one password rule per line (day 4)
+    #[structopt(long, parse(from_os_str))]
+    rules_file: Option<PathBuf>,
+
+    /// Show which rules rejected the candidates, and a sample of the matches (day 4)
+    #[structopt(long)]
+    explain: bool,
+
+    /// Body to start the orbital transfers from, instead of YOU (day 6)
+    #[structopt(long)]
+    from: Option<String>,
+
+    /// Body to transfer to, instead of SAN (day 6)
+    #[structopt(long)]
+    to: Option<String>,
+
+    /// Width of the image in pixels, 25 if not given (day 8)
+    #[structopt(long)]
+    width: Option<usize>,
+
+    /// Height of the image in pixels, 6 if not given (day 8)
+    #[structopt(long)]
+    height: Option<usize>,
+
+    /// How to print the decoded image: `blocks` (the default), `half-blocks`, `text` to read
+    /// out the letters, or `html` (day 8)
+    #[structopt(long)]
+    format: Option<String>,
+
+    /// Write the decoded image to a `.png` or `.ppm` file instead of solving (day 8)
+    #[structopt(long, parse(from_os_str))]
+    export: Option<PathBuf>,
+
+    /// Write every layer as a PNG to this directory instead of solving (day 8)
+    #[structopt(long, parse(from_os_str))]
+    dump_layers: Option<PathBuf>,
+
+    /// Size in pixels of each exported image pixel, 10 if not given (day 8)
+    #[structopt(long)]
+    scale: Option<usize>,
+
+    /// Colour to draw a kind of pixel with in HTML and exported images, e.g.
+    /// `--colour transparent=#f0f` or `--colour 7=#808080` (day 8)
+    #[structopt(long, number_of_values = 1)]
+    colour: Vec<String>,
+}
+
+macro_rules! define_parts {
+    ( $(($mod:ident, $n:expr),)* ) => {
+        define_parts!( $( ($mod, $n) ),* )
+    };
+
+    ( $(($mod:ident, $n:expr)),* ) => {
+        /// Run the day and part given on the command line.
+        pub fn run(opt: &Opt) -> Result<()> {
+            match (opt.day, opt.part) {
+                $(
+                    ($n, None) => $mod::main(Part::One, &opt),
+                    ($n, Some(part)) => $mod::main(Part::try_from(part)?, &opt),
+                )*
+
+                (day, None) => Err(Error::msg(format!("Unknown day {}", day))),
+
+                (day, Some(part)) => Err(Error::msg(format!("Unknown day {}, part {}", day, part))),
+            }
+        }
+    };
+}
+
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_8;
+
+// The days `run` knows about, and their numbers on the command line.
+define_parts!(
+    (day_1, 1),
+    (day_2, 2),
+    (day_3, 3),
+    (day_4, 4),
+    (day_5, 5),
+    (day_6, 6),
+    (day_8, 8)
+);
+
+pub mod geom;
+pub mod input;
+pub mod intcode;
+pub mod ocr;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl TryFrom<usize> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(other: usize) -> Result<Self> {
+        match other {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            other => Err(Error::msg(format!(
+                "Invalid part in cmdline arg: {}",
+                other
+            ))),
+        }
+    }
+}
+
+pub(crate) fn read_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(From::from)
+}
+
+/// Read the file given with `--input`, or `default` if there wasn't one.
+pub(crate) fn read_input(opt: &Opt, default: &str) -> Result<String> {
+    match &opt.input {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("failed to read {}: {}", path.display(), err)),
+        None => read_file(default),
+    }
+}
+
+/// The message of the error in `result`, for tests that check what went wrong. Panics if there
+/// is no error.
+#[cfg(test)]
+pub(crate) fn error_message<T, E: std::fmt::Display>(result: std::result::Result<T, E>) -> String {
+    match result {
+        Ok(_) => panic!("expected an error"),
+        Err(err) => err.to_string(),
+    }
+}
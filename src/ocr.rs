@@ -0,0 +1,171 @@
+//! Reading text drawn in the 6 pixel tall block font some puzzles answer with.
+
+#[cfg(test)]
+use crate::error_message;
+use crate::geom::Grid;
+use crate::{Error, Result};
+
+pub const GLYPH_HEIGHT: usize = 6;
+
+/// How many columns of an unrecognised glyph to show in the error. Most glyphs are this wide.
+const GLYPH_WIDTH: usize = 4;
+
+/// Every letter of the font that has turned up so far, drawn with `#` for lit pixels. Most are
+/// four pixels wide, but some need five.
+const GLYPHS: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// Read the letters in `image`, where `true` is a lit pixel.
+///
+/// The image has to be exactly one glyph tall, with the first glyph starting at the left edge and
+/// every glyph followed by one blank column. The blank column after the last glyph is optional.
+/// Fails on anything that isn't a known letter.
+pub fn recognise(image: &Grid<bool>) -> Result<String> {
+    if image.height() != GLYPH_HEIGHT {
+        return Err(Error::msg(format!(
+            "text must be {} pixels tall, got {}",
+            GLYPH_HEIGHT,
+            image.height()
+        )));
+    }
+
+    if image.width() == 0 {
+        return Err(Error::msg("an empty image has no text"));
+    }
+
+    let mut letters = String::new();
+    let mut start = 0;
+
+    while start < image.width() {
+        let (letter, width) = GLYPHS
+            .iter()
+            .map(|(letter, drawn)| (*letter, drawn, drawn.lines().next().unwrap_or("").len()))
+            .find(|(_, drawn, width)| {
+                start + width <= image.width()
+                    && glyph_at(image, start, *width) == **drawn
+                    && is_blank_column(image, start + width)
+            })
+            .map(|(letter, _, width)| (letter, width))
+            .ok_or_else(|| {
+                let width = GLYPH_WIDTH.min(image.width() - start);
+                Error::msg(format!(
+                    "unrecognised glyph at column {}:\n{}",
+                    start,
+                    glyph_at(image, start, width)
+                ))
+            })?;
+
+        letters.push(letter);
+        start += width + 1;
+    }
+
+    Ok(letters)
+}
+
+/// The `width` columns of `image` starting at `start`, drawn the same way as `GLYPHS`.
+fn glyph_at(image: &Grid<bool>, start: usize, width: usize) -> String {
+    image
+        .rows()
+        .map(|row| {
+            row[start..start + width]
+                .iter()
+                .map(|lit| if *lit { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether nothing is lit in column `col`, which counts as blank past the right edge too.
+fn is_blank_column(image: &Grid<bool>, col: usize) -> bool {
+    image.rows().all(|row| row.get(col).is_none_or(|lit| !*lit))
+}
+
+#[cfg(test)]
+fn draw(letters: &str) -> Grid<bool> {
+    let glyphs = letters
+        .chars()
+        .map(|letter| {
+            GLYPHS
+                .iter()
+                .find(|(known, _)| *known == letter)
+                .unwrap()
+                .1
+                .lines()
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let rows = (0..GLYPH_HEIGHT)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row])
+                .collect::<Vec<_>>()
+                .join(".")
+                .chars()
+                .map(|c| c == '#')
+                .collect()
+        })
+        .collect();
+
+    Grid::from_rows(rows).unwrap()
+}
+
+#[test]
+fn recognise_test() {
+    let letters = GLYPHS.iter().map(|(letter, _)| *letter).collect::<String>();
+
+    assert_eq!(recognise(&draw(&letters)).unwrap(), letters);
+    assert_eq!(recognise(&draw("LHCPH")).unwrap(), "LHCPH");
+    assert_eq!(recognise(&draw("YAY")).unwrap(), "YAY");
+}
+
+#[test]
+fn recognise_errors_test() {
+    assert_eq!(
+        error_message(recognise(&Grid::new(4, 5, false))),
+        "text must be 6 pixels tall, got 5"
+    );
+    assert_eq!(
+        error_message(recognise(&Grid::new(0, 6, false))),
+        "an empty image has no text"
+    );
+    assert_eq!(
+        error_message(recognise(&Grid::new(7, 6, false))),
+        "unrecognised glyph at column 0:\n....\n....\n....\n....\n....\n...."
+    );
+
+    let mut image = draw("AB");
+    image[(0, 8)] = true;
+    assert_eq!(
+        error_message(recognise(&image)),
+        "unrecognised glyph at column 5:\n####\n#..#\n###.\n#..#\n#..#\n###."
+    );
+
+    // A lit pixel between two letters means they aren't separate glyphs after all.
+    let mut image = draw("LL");
+    image[(2, 4)] = true;
+    assert_eq!(
+        error_message(recognise(&image)),
+        "unrecognised glyph at column 0:\n#...\n#...\n#...\n#...\n#...\n####"
+    );
+}
@@ -0,0 +1,79 @@
+//! Every solution run on the real puzzle input, checked against `answers.md`.
+
+mod common;
+
+use common::aoc;
+
+/// The known answers in `answers.md`, as `(day, part, answer)`. Parts answered with `?` are
+/// left out.
+fn answers() -> Vec<(u32, u32, String)> {
+    let answers =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/answers.md")).unwrap();
+
+    let mut day = None;
+    let mut known = vec![];
+
+    for line in answers.lines() {
+        if let Some(n) = line.strip_prefix("# Day ") {
+            day = Some(n.trim().parse().unwrap());
+        } else if let Some(rest) = line.strip_prefix("Part ") {
+            let (part, answer) = rest.split_once(':').unwrap();
+            let answer = answer.trim();
+
+            if answer != "?" {
+                known.push((
+                    day.unwrap(),
+                    part.trim().parse().unwrap(),
+                    answer.to_string(),
+                ));
+            }
+        }
+    }
+
+    known
+}
+
+/// Extra arguments that make a solution print its answer rather than a picture of it.
+fn args(day: u32) -> &'static [&'static str] {
+    match day {
+        8 => &["--format", "text"],
+        _ => &[],
+    }
+}
+
+/// What to type into the solutions that ask for input.
+fn stdin(day: u32) -> &'static str {
+    match day {
+        // The ID of the ship's air conditioner unit.
+        5 => "1\n",
+        _ => "",
+    }
+}
+
+#[test]
+fn answers_md() {
+    let answers = answers();
+    assert!(!answers.is_empty(), "found no answers");
+
+    let wrong = answers
+        .iter()
+        .filter_map(|(day, part, answer)| {
+            let (day_arg, part_arg) = (day.to_string(), part.to_string());
+            let mut cmd = vec![day_arg.as_str(), part_arg.as_str()];
+            cmd.extend(args(*day));
+
+            let got = aoc(&cmd, stdin(*day));
+
+            if got == *answer {
+                None
+            } else {
+                Some(format!(
+                    "day {} part {}: expected {}, got {}",
+                    day, part, answer, got
+                ))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert!(wrong.is_empty(), "{}", wrong.join("\n"));
+}
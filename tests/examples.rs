@@ -0,0 +1,134 @@
+//! Every solution run on the examples from the puzzle descriptions, in `input/examples/`.
+
+mod common;
+
+use aoc::day_4::{Monotonic, RuleSet, Run};
+use common::aoc;
+
+#[test]
+fn day_1() {
+    let args = |part| ["1", part, "--input", "input/examples/day_1"];
+
+    assert_eq!(aoc(&args("1"), ""), (2 + 2 + 654 + 33583).to_string());
+    assert_eq!(aoc(&args("2"), ""), (2 + 2 + 966 + 50346).to_string());
+}
+
+#[test]
+fn day_2() {
+    // Address 1 and 2 already hold 9 and 10, so the example runs as it is.
+    let args = [
+        "2",
+        "1",
+        "--input",
+        "input/examples/day_2",
+        "--noun",
+        "9",
+        "--verb",
+        "10",
+    ];
+
+    assert_eq!(aoc(&args, ""), "3500");
+}
+
+#[test]
+fn day_3() {
+    let args = |part, example| ["3", part, "--input", example];
+
+    assert_eq!(aoc(&args("1", "input/examples/day_3_a"), ""), "6");
+    assert_eq!(aoc(&args("1", "input/examples/day_3_b"), ""), "159");
+    assert_eq!(aoc(&args("1", "input/examples/day_3_c"), ""), "135");
+    assert_eq!(aoc(&args("2", "input/examples/day_3_a"), ""), "30");
+    assert_eq!(aoc(&args("2", "input/examples/day_3_b"), ""), "610");
+    assert_eq!(aoc(&args("2", "input/examples/day_3_c"), ""), "410");
+}
+
+/// The range starts and ends with the examples that meet the rules of part 1 and part 2.
+#[test]
+fn day_4() {
+    let args = |part| ["4", part, "--input", "input/examples/day_4"];
+
+    // 111111 to 111119, and 111122.
+    assert_eq!(aoc(&args("1"), ""), "10");
+    // Only 111122, the rest have no pair that isn't part of a longer run.
+    assert_eq!(aoc(&args("2"), ""), "1");
+
+    let part_1 = RuleSet::part_1();
+    assert!(!part_1.check(223450));
+    assert!(!part_1.check(123789));
+
+    let part_2 = RuleSet::part_2();
+    assert!(part_2.check(112233));
+    assert!(!part_2.check(123444));
+}
+
+/// Used to be an ignored unit test of `validate_two_identical_adjacent_digits_small_group`,
+/// which `Run::Exactly(2)` replaced.
+#[test]
+fn day_4_small_group() {
+    let small_group = RuleSet::new().with(Run::Exactly(2));
+
+    assert!(small_group.check(112233), "112233");
+    assert!(!small_group.check(123444), "123444");
+    assert!(small_group.check(111122), "111122");
+    assert!(small_group.check(11112212222), "11112212222");
+
+    let increasing = small_group.monotonic(Monotonic::Increasing);
+    assert!(!increasing.check(11112212222), "11112212222");
+}
+
+#[test]
+fn day_5() {
+    let args = ["5", "1", "--input", "input/examples/day_5"];
+
+    assert_eq!(aoc(&args, "42\n"), "42");
+    assert_eq!(aoc(&args, "-7\n"), "-7");
+}
+
+#[test]
+fn day_6() {
+    let args = |part| ["6", part, "--input", "input/examples/day_6"];
+
+    assert_eq!(aoc(&args("1"), ""), "54");
+    assert_eq!(aoc(&args("2"), ""), "4");
+
+    let mut transfers = args("2").to_vec();
+    transfers.extend(&["--from", "YOU", "--to", "SAN"]);
+    assert_eq!(aoc(&transfers, ""), "4\nK -> J -> E -> D -> I");
+}
+
+#[test]
+fn day_8() {
+    assert_eq!(
+        aoc(
+            &[
+                "8",
+                "1",
+                "--input",
+                "input/examples/day_8_a",
+                "--width",
+                "3",
+                "--height",
+                "2"
+            ],
+            ""
+        ),
+        "1"
+    );
+    // Blocks are the default, and unlike the letters they work for any size of image.
+    assert_eq!(
+        aoc(
+            &[
+                "8",
+                "2",
+                "--input",
+                "input/examples/day_8_b",
+                "--width",
+                "2",
+                "--height",
+                "2",
+            ],
+            ""
+        ),
+        " █\n█"
+    );
+}